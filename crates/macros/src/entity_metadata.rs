@@ -243,13 +243,15 @@ pub fn entity_metadata_serializable_macro(input: TokenStream) -> TokenStream {
                     }
                 }
 
+                // variants without any fields only write the end marker
+                let binding = if fields.is_empty() { quote! { _ } } else { quote! { metadata } };
                 write_size_stream.push(quote! {
-                    Self::#ident(metadata) => {
-                        #(#variant_write_sizes)+*
+                    Self::#ident(#binding) => {
+                        0 #(+ #variant_write_sizes)*
                     }
                 });
                 write_stream.extend(quote! {
-                    Self::#ident(metadata) => {
+                    Self::#ident(#binding) => {
                         #variant_writes
                     }
                 });
@@ -14,6 +14,14 @@ impl<'a> CommandParse<'a> for i32 {
     }
 }
 
+impl<'a> CommandParse<'a> for u64 {
+    fn parse(reader: &'a mut StringReader<'a>) -> anyhow::Result<Self> {
+        let str = reader.read_word();
+        let int = Self::from_str(str)?;
+        Ok(int)
+    }
+}
+
 impl<'a> CommandParse<'a> for &'a str {
     fn parse(reader: &'a mut StringReader<'a>) -> anyhow::Result<Self> {
         let s = reader.read_word();
@@ -24,6 +32,17 @@ impl<'a> CommandParse<'a> for &'a str {
     }
 }
 
+// optional arguments, only if nothing is left to parse
+impl<'a, T: CommandParse<'a>> CommandParse<'a> for Option<T> {
+    fn parse(reader: &'a mut StringReader<'a>) -> anyhow::Result<Self> {
+        reader.skip_whitespace();
+        if reader.remaining().is_empty() {
+            return Ok(None)
+        }
+        Ok(Some(T::parse(reader)?))
+    }
+}
+
 pub struct GreedyString<'a> {
     pub str: &'a str
 }
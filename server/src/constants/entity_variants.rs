@@ -4,15 +4,22 @@ use bytes::BytesMut;
 #[repr(i8)]
 #[derive(Copy, Clone)]
 pub enum EntityVariant {
+    Creeper = 50,
+    Skeleton = 51,
     Zombie = 54,
+    Silverfish = 60,
+    Blaze = 61,
     Bat = 65,
 }
 
 #[repr(i8)]
 #[derive(Copy, Clone)]
 pub enum ObjectVariant {
+    Item = 2,
+    Arrow = 60,
     EnderPearl = 65,
-    FallingBlock = 70
+    FallingBlock = 70,
+    ArmorStand = 78,
 }
 
 impl PacketSerializable for ObjectVariant {
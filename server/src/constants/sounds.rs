@@ -12,6 +12,12 @@ pub enum Sound {
     DonkeyHit,
     NoteHarp,
     NoteHat,
+    EndermanPortal,
+    VillagerNo,
+    RandomOrb,
+    RandomChestOpen,
+    RandomBow,
+    RandomBowHit,
 }
 
 impl Sound {
@@ -26,6 +32,12 @@ impl Sound {
             Sound::DonkeyHit => "mob.horse.donkey.hit",
             Sound::NoteHarp => "note.harp",
             Sound::NoteHat => "note.hat",
+            Sound::EndermanPortal => "mob.endermen.portal",
+            Sound::VillagerNo => "mob.villager.no",
+            Sound::RandomOrb => "random.orb",
+            Sound::RandomChestOpen => "random.chestopen",
+            Sound::RandomBow => "random.bow",
+            Sound::RandomBowHit => "random.bowhit",
         }
     }
 }
@@ -44,7 +44,7 @@ impl<W: WorldExtension + 'static> EntityAppearance<W> for MobAppearance {
             velocity_x: entity.velocity.z,
             velocity_y: entity.velocity.y,
             velocity_z: entity.velocity.z,
-            metadata: self.metadata,
+            metadata: self.metadata.clone(),
         });
         player.write_packet(&EntityYawRotate {
             entity_id: entity.id,
@@ -70,6 +70,10 @@ impl<W: WorldExtension + 'static> Entities<W> {
         self.id_to_entities.get(&id)
     }
 
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.ecs.get_entity(entity).is_ok()
+    }
+
     pub fn get_entity(&'_ self, entity: Entity) -> EntityRef<'_> {
         self.ecs.entity(entity)
     }
@@ -99,4 +103,10 @@ impl<W: WorldExtension + 'static> Entities<W> {
         }
         entity.despawn();
     }
+
+    /// despawns every entity, without sending anything to players
+    pub fn clear(&mut self) {
+        self.ecs.clear_entities();
+        self.id_to_entities.clear();
+    }
 }
\ No newline at end of file
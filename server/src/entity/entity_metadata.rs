@@ -1,4 +1,5 @@
 use crate::entity::entity_metadata_serializable::MetadataSerializable;
+use crate::inventory::item_stack::ItemStack;
 use crate::network::packets::packet_serialize::PacketSerializable;
 use enumset::{EnumSet, EnumSetType};
 use macros::entity_metadata_serializable;
@@ -14,23 +15,56 @@ pub enum SkinLayers {
     Hat
 }
 
+// shared by every entity, at index 0
+#[derive(EnumSetType, Debug)]
+pub enum EntityFlags {
+    OnFire = 0,
+    Sneaking = 1,
+    Sprinting = 3,
+    Using = 4,
+    Invisible = 5,
+}
+
 entity_metadata_serializable! {
     #[derive(Copy, Clone)]
     pub struct PlayerMetadata {
+        0 => pub flags: EnumSet<EntityFlags> = EnumSet::empty(),
         10 => pub layers: EnumSet<SkinLayers> = EnumSet::all(),
     }
 }
 
 entity_metadata_serializable! {
-    #[derive(Debug, Copy, Clone)]
+    #[derive(Debug, Clone)]
     pub enum EntityMetadata {
         Zombie {
             12 => pub is_baby: bool = false,
             13 => pub is_villager: bool = false,
         },
+        Skeleton {
+            13 => pub is_wither: bool = false,
+        },
+        Creeper {
+            // -1 idle, 1 fusing
+            16 => pub state: i8 = -1,
+            17 => pub powered: bool = false,
+        },
+        Silverfish {},
+        Blaze {
+            16 => pub on_fire: bool = false,
+        },
         Bat {
             0 => pub flags: u8 = 0,
             16 => pub hanging: bool = false,
+        },
+        ArmorStand {
+            0 => pub flags: EnumSet<EntityFlags> = EnumSet::empty(),
+            2 => pub custom_name: String = String::new(),
+            3 => pub custom_name_visible: bool = false,
+            // small 0x01, has arms 0x04, no base plate 0x08, marker 0x10
+            10 => pub armor_stand_flags: u8 = 0,
+        },
+        Item {
+            10 => pub item: Option<ItemStack> = None,
         }
     }
 }
\ No newline at end of file
@@ -13,7 +13,7 @@ type Receiver<T> = UnboundedReceiver<T>;
 
 
 pub fn start_network(
-    ip: &'static str,
+    ip: String,
     status: Status,
 ) -> (Sender<NetworkThreadMessage>, Receiver<MainThreadMessage>) {
     let (network_tx, network_rx) = unbounded_channel::<NetworkThreadMessage>();
@@ -23,13 +23,13 @@ pub fn start_network(
 }
 
 async fn run_network_thread(
-    ip: &'static str,
+    ip: String,
     mut status: Status,
     mut network_rx: Receiver<NetworkThreadMessage>,
     network_tx: Sender<NetworkThreadMessage>,
     main_tx: Sender<MainThreadMessage>,
 ) {
-    let listener = TcpListener::bind(ip).await.unwrap();
+    let listener = TcpListener::bind(&ip).await.unwrap();
     println!("Network thread listening on {ip}");
 
     // slotmap is faster than a hashmap and works just as well for us here
@@ -30,7 +30,7 @@ register_packets! {
     PositionLook = 0x08;
     // SetHotbarSlot = 0x09;
     // EntityUsedBed = 0x0a;
-    // SwingAnimation = 0x0b
+    EntityAnimation = 0x0b;
     SpawnPlayer = 0x0c;
     CollectItem = 0x0d;
     SpawnObject = 0x0e;
@@ -166,6 +166,14 @@ packet_serializable! {
     }
 }
 
+packet_serializable! {
+    pub struct EntityAnimation {
+        pub entity_id: VarInt,
+        // 0 swing arm, 1 take damage, 2 leave bed, 3 eat food, 4 critical effect, 5 magic critical effect
+        pub animation_id: u8,
+    }
+}
+
 packet_serializable! {
     pub struct SpawnPlayer {
         pub entity_id: i32 => &VarInt(self.entity_id),
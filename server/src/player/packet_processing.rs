@@ -1,8 +1,11 @@
 use crate::entity::components::Interactable;
+use crate::entity::entity_metadata::EntityFlags;
 use crate::inventory::menu::OpenContainer;
+use crate::network::binary::var_int::VarInt;
 use crate::network::packets::packet::ProcessPacket;
+use crate::network::protocol::play::clientbound::EntityAnimation;
 use crate::network::protocol::play::serverbound;
-use crate::network::protocol::play::serverbound::{ArmSwing, ChatMessage, ClickWindow, ClientSettings, ClientStatus, CreativeInventoryAction, HeldItemChange, PlayerAction, PlayerActionType, PlayerBlockPlacement, PlayerDigging, PlayerLook, PlayerPosition, PlayerPositionLook, PlayerUpdate, TabComplete, UseEntity};
+use crate::network::protocol::play::serverbound::{ArmSwing, ChatMessage, ClickWindow, ClientSettings, ClientStatus, CreativeInventoryAction, EntityInteractionType, HeldItemChange, PlayerAction, PlayerActionType, PlayerBlockPlacement, PlayerDigging, PlayerLook, PlayerPosition, PlayerPositionLook, PlayerUpdate, TabComplete, UseEntity};
 use crate::player::player::{Player, PlayerExtension};
 use crate::types::direction::Direction3D;
 use enumset::EnumSet;
@@ -32,10 +35,14 @@ impl ProcessPacket for UseEntity {
         let world = player.world_mut();
         if let Some(entity_id) = world.entities.mc_id_to_entity(self.entity_id.0) {
 
-            let entity = world.entities.get_entity_mut(*entity_id);
+            let entity_id = *entity_id;
+            let entity = world.entities.get_entity_mut(entity_id);
             if let Some(interactable) = entity.get::<Interactable<P::World>>() {
                 (interactable.callback)(entity, player);
             }
+            if matches!(self.action, EntityInteractionType::Attack) {
+                P::attack(player, entity_id)
+            }
         }
     }
 }
@@ -122,18 +129,35 @@ impl ProcessPacket for HeldItemChange {
 
 // will be useful if we want to add stuff like mage beam
 impl ProcessPacket for ArmSwing {
-    fn process<P: PlayerExtension>(&self, _: &mut Player<P>) {
-        // lc
+    fn process<P: PlayerExtension>(&self, player: &mut Player<P>) {
+        let packet = EntityAnimation {
+            entity_id: VarInt(player.entity_id),
+            animation_id: 0,
+        };
+        player.for_each_viewer(|viewer| viewer.write_packet(&packet));
     }
 }
 
 impl ProcessPacket for PlayerAction {
     fn process<P : PlayerExtension>(&self, player: &mut Player<P>) {
         match self.action {
-            PlayerActionType::StartSneaking => player.is_sneaking = true,
-            PlayerActionType::StopSneaking => player.is_sneaking = false,
-            _ => {}
+            PlayerActionType::StartSneaking => {
+                player.is_sneaking = true;
+                player.metadata.flags.insert(EntityFlags::Sneaking);
+            }
+            PlayerActionType::StopSneaking => {
+                player.is_sneaking = false;
+                player.metadata.flags.remove(EntityFlags::Sneaking);
+            }
+            PlayerActionType::StartSprinting => {
+                player.metadata.flags.insert(EntityFlags::Sprinting);
+            }
+            PlayerActionType::StopSprinting => {
+                player.metadata.flags.remove(EntityFlags::Sprinting);
+            }
+            _ => return
         }
+        player.dirty_metadata = true;
     }
 }
 
@@ -12,7 +12,7 @@ use crate::network::packets::packet::IdentifiedPacket;
 use crate::network::packets::packet_buffer::PacketBuffer;
 use crate::network::packets::packet_serialize::PacketSerializable;
 use crate::network::protocol::play::clientbound;
-use crate::network::protocol::play::clientbound::{Chat, ConfirmTransaction, PacketPlayerMetadata, PlayerData, PlayerListItem, SoundEffect, WindowItems};
+use crate::network::protocol::play::clientbound::{Chat, ConfirmTransaction, DestroyEntites, EntityEquipment, EntityMoveRotate, EntityTeleport, EntityYawRotate, PacketPlayerMetadata, PlayerData, PlayerListItem, SoundEffect, SpawnPlayer, WindowItems};
 use crate::network::protocol::play::serverbound::PlayerDiggingAction;
 use crate::player::packet_processing::BlockInteractResult;
 use crate::types::aabb::AABB;
@@ -22,6 +22,7 @@ use crate::world::chunk::chunk_grid;
 use crate::world::chunk::chunk_grid::ChunkDiff;
 use crate::world::world::VIEW_DISTANCE;
 use crate::world::world::{World, WorldExtension};
+use bevy_ecs::entity::Entity;
 use fstr::FString;
 use glam::{dvec3, DVec3, IVec3, Vec3};
 use std::cell::UnsafeCell;
@@ -61,6 +62,10 @@ pub trait PlayerExtension : Sized {
     fn interact(player: &mut Player<Self>, item: Option<ItemStack>, block: Option<BlockInteractResult>) {
 
     }
+
+    fn attack(player: &mut Player<Self>, entity: Entity) {
+
+    }
 }
 
 pub struct Player<E : PlayerExtension> {
@@ -91,6 +96,8 @@ pub struct Player<E : PlayerExtension> {
     pub(super) open_container: UnsafeCell<OpenContainer<E>>,
     pub inventory: Inventory<E::Item>,
     pub held_slot: u8,
+    // last held item other players were sent
+    sent_held_item: Option<ItemStack>,
 
     // todo: do this for other packets too
     pub sent_block_placement: bool,
@@ -124,7 +131,7 @@ impl<E : PlayerExtension> Player<E> {
             client_id,
             entity_id,
             gamemode,
-            metadata: PlayerMetadata { layers: Default::default() },
+            metadata: PlayerMetadata { flags: Default::default(), layers: Default::default() },
             dirty_metadata: false,
             position,
             yaw,
@@ -139,6 +146,7 @@ impl<E : PlayerExtension> Player<E> {
             window_id: 0,
             inventory: Inventory::new(),
             held_slot: 0,
+            sent_held_item: None,
 
             sent_block_placement: false,
             ticks_existed: 0,
@@ -210,10 +218,12 @@ impl<E : PlayerExtension> Player<E> {
         E::tick(self);
 
         if self.dirty_metadata {
-            self.write_packet(&PacketPlayerMetadata {
+            let packet = PacketPlayerMetadata {
                 entity_id: VarInt(self.entity_id),
                 metadata: self.metadata,
-            });
+            };
+            self.write_packet(&packet);
+            self.for_each_viewer(|viewer| viewer.write_packet(&packet));
             self.dirty_metadata = false;
         }
 
@@ -242,13 +252,38 @@ impl<E : PlayerExtension> Player<E> {
                     if diff == ChunkDiff::New {
                         chunk.write_chunk_data(x, z, true, &mut self.packet_buffer);
                         chunk.write_spawn_entities(self);
+                        for other in chunk.players() {
+                            if other.client_id != self.client_id {
+                                other.enter_player_view(self);
+                                self.enter_player_view(other);
+                            }
+                        }
                     } else {
                         chunk.write_despawn_entities(self);
+                        for other in chunk.players() {
+                            other.leave_player_view(self);
+                            self.leave_player_view(other);
+                        }
                         self.write_packet(&chunk_grid::get_unload_chunk_packet(x, z));
                     }
                 }
             )
         }
+
+        if self.position != self.last_position || self.yaw != self.last_yaw || self.pitch != self.last_pitch {
+            self.write_movement();
+        }
+
+        let held_item = get_item_stack(self.get_held_item());
+        if held_item != self.sent_held_item {
+            let packet = EntityEquipment {
+                entity_id: VarInt(self.entity_id),
+                item_slot: 0,
+                item_stack: held_item.clone(),
+            };
+            self.for_each_viewer(|viewer| viewer.write_packet(&packet));
+            self.sent_held_item = held_item;
+        }
         
         // copy packet buffers from chunks around
         let min_x = chunk_x - VIEW_DISTANCE;
@@ -267,15 +302,102 @@ impl<E : PlayerExtension> Player<E> {
         self.packet_buffer.copy_from(&self.world().global_packet_buffer);
         self.sent_block_placement = false;
         self.last_position = self.position;
+        self.last_yaw = self.yaw;
+        self.last_pitch = self.pitch;
         self.flush_packets();
     }
 
+    // calls the closure for every other player that can see this player
+    pub fn for_each_viewer<F: FnMut(&mut Player<E>)>(&mut self, mut callback: F) {
+        let client_id = self.client_id;
+        self.world_mut().for_each_player_in_view(self.position, |player| {
+            if player.client_id != client_id {
+                callback(player)
+            }
+        })
+    }
+
+    // spawns this player for another player
+    pub fn enter_player_view(&self, player: &mut Player<E>) {
+        player.write_packet(&SpawnPlayer {
+            entity_id: self.entity_id,
+            uuid: self.profile.uuid,
+            x: self.position.x,
+            y: self.position.y,
+            z: self.position.z,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            current_item: 0,
+            metadata: self.metadata,
+        });
+        player.write_packet(&EntityYawRotate {
+            entity_id: self.entity_id,
+            yaw: self.yaw,
+        });
+        player.write_packet(&EntityEquipment {
+            entity_id: VarInt(self.entity_id),
+            item_slot: 0,
+            item_stack: get_item_stack(self.get_held_item()),
+        });
+    }
+
+    pub fn leave_player_view(&self, player: &mut Player<E>) {
+        player.write_packet(&DestroyEntites {
+            entities: vec![VarInt(self.entity_id)],
+        })
+    }
+
+    fn write_movement(&mut self) {
+        // relative moves are in 1/32 of a block and have to fit in a byte,
+        // otherwise it has to be a teleport
+        let delta = (self.position * 32.0).floor() - (self.last_position * 32.0).floor();
+        let yaw = (self.yaw * 256.0 / 360.0) as i32 as i8;
+        let pitch = (self.pitch * 256.0 / 360.0) as i32 as i8;
+
+        if delta.abs().max_element() <= i8::MAX as f64 {
+            let packet = EntityMoveRotate {
+                entity_id: VarInt(self.entity_id),
+                pos_x: delta.x as i8,
+                pos_y: delta.y as i8,
+                pos_z: delta.z as i8,
+                yaw,
+                pitch,
+                on_ground: self.on_ground,
+            };
+            self.for_each_viewer(|viewer| viewer.write_packet(&packet));
+        } else {
+            let packet = EntityTeleport {
+                entity_id: self.entity_id,
+                pos_x: self.position.x,
+                pos_y: self.position.y,
+                pos_z: self.position.z,
+                yaw: self.yaw,
+                pitch: self.pitch,
+                on_ground: self.on_ground,
+            };
+            self.for_each_viewer(|viewer| viewer.write_packet(&packet));
+        }
+
+        let packet = EntityYawRotate {
+            entity_id: self.entity_id,
+            yaw: self.yaw,
+        };
+        self.for_each_viewer(|viewer| viewer.write_packet(&packet));
+    }
+
     pub fn send_message(&mut self, str: &str) {
         self.write_packet(&Chat {
             component: ChatComponent::new(str),
             chat_type: 0,
         });
     }
+
+    pub fn send_action_bar(&mut self, str: &str) {
+        self.write_packet(&Chat {
+            component: ChatComponent::new(str),
+            chat_type: 2,
+        });
+    }
     
     pub fn play_sound_at(&mut self, sound: Sound, volume: f32, pitch: f32, position: DVec3) {
         self.write_packet(&SoundEffect {
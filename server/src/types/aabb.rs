@@ -57,4 +57,29 @@ impl AABB {
     pub fn offset(self, dvec3: DVec3) -> AABB {
         AABB::new(self.min + dvec3, self.max + dvec3)
     }
+
+    /// whether the line segment between from and to passes through this box
+    pub fn intersects_segment(&self, from: DVec3, to: DVec3) -> bool {
+        let direction = to - from;
+        let mut t_min: f64 = 0.0;
+        let mut t_max: f64 = 1.0;
+
+        for axis in 0..3 {
+            let (origin, delta, min, max) = (from[axis], direction[axis], self.min[axis], self.max[axis]);
+            if delta == 0.0 {
+                if origin < min || origin > max {
+                    return false;
+                }
+                continue;
+            }
+            let a = (min - origin) / delta;
+            let b = (max - origin) / delta;
+            t_min = t_min.max(a.min(b));
+            t_max = t_max.min(a.max(b));
+            if t_min > t_max {
+                return false;
+            }
+        }
+        true
+    }
 }
\ No newline at end of file
@@ -74,6 +74,12 @@ impl<W : WorldExtension + 'static> Chunk<W> {
         }
     }
 
+    /// removes every block, players and entities are kept
+    pub fn clear_blocks(&mut self) {
+        self.chunk_sections = [const { None }; 16];
+        self.dirty = true;
+    }
+
     pub fn write_chunk_data(&mut self, x: i32, z: i32, new: bool, into: &mut PacketBuffer) {
         // this only writes chunks if the x and z are the same,
         // so for an empty chunk this doesn't work
@@ -173,6 +173,12 @@ impl<W: WorldExtension + 'static> ChunkGrid<W> {
             self.set_block_at(block, x, y, z)
         })
     }
+
+    pub fn clear_blocks(&mut self) {
+        for chunk in self.chunks.iter_mut() {
+            chunk.clear_blocks()
+        }
+    }
 }
 
 pub fn get_unload_chunk_packet(chunk_x: i32, chunk_z: i32) -> ChunkData {
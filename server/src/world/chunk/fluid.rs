@@ -0,0 +1,297 @@
+use crate::block::blocks::Block;
+use crate::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use crate::world::chunk::chunk_grid::ChunkGrid;
+use crate::WorldExtension;
+use glam::{ivec3, IVec3};
+use std::collections::HashMap;
+
+const HORIZONTAL: [IVec3; 4] = [
+    ivec3(0, 0, -1),
+    ivec3(1, 0, 0),
+    ivec3(0, 0, 1),
+    ivec3(-1, 0, 0),
+];
+
+// same as vanilla, 0 is a source, 1 to 7 is flowing water and 8 is falling water
+const SOURCE: u8 = 0;
+const MAX_LEVEL: u8 = 7;
+const FALLING: u8 = 8;
+
+// how far water looks for a drop to flow towards
+const SLOPE_DISTANCE: u8 = 4;
+const NO_DROP: u8 = u8::MAX;
+
+/// anything blocks can be read from and written to
+pub trait BlockAccess {
+    fn get_block(&self, position: IVec3) -> Block;
+    fn set_block(&mut self, block: Block, position: IVec3);
+}
+
+impl<W: WorldExtension + 'static> BlockAccess for ChunkGrid<W> {
+    fn get_block(&self, position: IVec3) -> Block {
+        self.get_block_at(position.x, position.y, position.z)
+    }
+    fn set_block(&mut self, block: Block, position: IVec3) {
+        self.set_block_at(block, position.x, position.y, position.z)
+    }
+}
+
+impl BlockAccess for HashMap<IVec3, Block> {
+    fn get_block(&self, position: IVec3) -> Block {
+        self.get(&position).copied().unwrap_or(Block::Air)
+    }
+    fn set_block(&mut self, block: Block, position: IVec3) {
+        self.insert(position, block);
+    }
+}
+
+/// Water flowing out of a set of sources, simulated by the server.
+///
+/// It follows vanilla rules closely enough to line up with what clients expect,
+/// falling when it can and otherwise spreading towards the nearest drop,
+/// but every block is updated at once each tick so the result only depends on the blocks around it.
+/// Only water placed by the flow is tracked, other water in the world acts like air.
+pub struct FluidFlow {
+    sources: Vec<IVec3>,
+    water: DeterministicHashMap<IVec3, u8>,
+}
+
+impl FluidFlow {
+
+    pub fn new(sources: Vec<IVec3>) -> Self {
+        Self {
+            sources,
+            water: DeterministicHashMap::default(),
+        }
+    }
+
+    pub fn contains(&self, position: IVec3) -> bool {
+        self.water.contains_key(&position)
+    }
+
+    pub fn water(&self) -> impl Iterator<Item = &IVec3> {
+        self.water.keys()
+    }
+
+    /// removes all water placed by this flow, it starts over from the sources next tick
+    pub fn clear(&mut self, blocks: &mut impl BlockAccess) {
+        for (position, _) in self.water.drain() {
+            blocks.set_block(Block::Air, position);
+        }
+    }
+
+    /// spreads the water by one block, returns false once it has nowhere left to go
+    pub fn tick(&mut self, blocks: &mut impl BlockAccess) -> bool {
+        let mut updates: DeterministicHashMap<IVec3, u8> = DeterministicHashMap::default();
+
+        for source in self.sources.iter() {
+            if !self.water.contains_key(source) && blocks.get_block(*source) == Block::Air {
+                updates.insert(*source, SOURCE);
+            }
+        }
+
+        for (&position, &level) in self.water.iter() {
+            let below = position - IVec3::Y;
+            if self.can_flow_into(blocks, below, FALLING) {
+                merge(&mut updates, below, FALLING);
+                continue;
+            }
+            // like vanilla, water only spreads out once it can't fall any further. a source
+            // can't once there's water below it, flowing water has to land on something solid
+            if level != SOURCE && !blocks_flow(blocks.get_block(below)) {
+                continue;
+            }
+
+            let next_level = if level == FALLING { 1 } else { level + 1 };
+            if next_level > MAX_LEVEL {
+                continue;
+            }
+            for direction in self.flow_directions(blocks, position) {
+                let neighbour = position + direction;
+                if self.can_flow_into(blocks, neighbour, next_level) {
+                    merge(&mut updates, neighbour, next_level);
+                }
+            }
+        }
+
+        updates.retain(|position, level| self.water.get(position).is_none_or(|current| is_better(*level, *current)));
+        for (position, level) in updates.iter() {
+            blocks.set_block(Block::FlowingWater { level: *level }, *position);
+            self.water.insert(*position, *level);
+        }
+        !updates.is_empty()
+    }
+
+    fn can_flow_into(&self, blocks: &impl BlockAccess, position: IVec3, level: u8) -> bool {
+        // nothing below the world to land on
+        if position.y < 0 {
+            return false;
+        }
+        match self.water.get(&position) {
+            Some(current) => is_better(level, *current),
+            None => blocks.get_block(position) == Block::Air,
+        }
+    }
+
+    fn is_source(&self, position: IVec3) -> bool {
+        self.water.get(&position) == Some(&SOURCE)
+    }
+
+    // like vanilla, water only flows in the directions with the shortest path to a drop,
+    // or every direction if there is no drop nearby
+    fn flow_directions(&self, blocks: &impl BlockAccess, position: IVec3) -> Vec<IVec3> {
+        let costs = HORIZONTAL.map(|direction| {
+            let neighbour = position + direction;
+            if blocks_flow(blocks.get_block(neighbour)) || self.is_source(neighbour) {
+                None
+            } else if !blocks_flow(blocks.get_block(neighbour - IVec3::Y)) {
+                Some(0)
+            } else {
+                Some(self.flow_cost(blocks, neighbour, 1, -direction))
+            }
+        });
+        let min = costs.iter().flatten().copied().min();
+        HORIZONTAL.into_iter()
+            .zip(costs)
+            .filter(|(_, cost)| cost.is_some() && *cost == min)
+            .map(|(direction, _)| direction)
+            .collect()
+    }
+
+    fn flow_cost(&self, blocks: &impl BlockAccess, position: IVec3, distance: u8, came_from: IVec3) -> u8 {
+        let mut cost = NO_DROP;
+        for direction in HORIZONTAL {
+            if direction == came_from {
+                continue;
+            }
+            let neighbour = position + direction;
+            if blocks_flow(blocks.get_block(neighbour)) || self.is_source(neighbour) {
+                continue;
+            }
+            if !blocks_flow(blocks.get_block(neighbour - IVec3::Y)) {
+                return distance;
+            }
+            if distance < SLOPE_DISTANCE {
+                cost = cost.min(self.flow_cost(blocks, neighbour, distance + 1, -direction));
+            }
+        }
+        cost
+    }
+}
+
+impl<W: WorldExtension + 'static> ChunkGrid<W> {
+    /// spreads the flow by one block, see [FluidFlow::tick]
+    pub fn tick_fluid(&mut self, flow: &mut FluidFlow) -> bool {
+        flow.tick(self)
+    }
+}
+
+fn blocks_flow(block: Block) -> bool {
+    !matches!(block, Block::Air | Block::FlowingWater { .. } | Block::StillWater { .. })
+}
+
+// falling water replaces flowing water, otherwise the lowest level wins
+fn is_better(level: u8, current: u8) -> bool {
+    match (level, current) {
+        (_, SOURCE) => false,
+        (FALLING, FALLING) => false,
+        (FALLING, _) => true,
+        (_, FALLING) => false,
+        _ => level < current,
+    }
+}
+
+fn merge(updates: &mut DeterministicHashMap<IVec3, u8>, position: IVec3, level: u8) {
+    match updates.get(&position) {
+        Some(current) if !is_better(level, *current) => {}
+        _ => {
+            updates.insert(position, level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // anything falling through a hole lands on the bottom layer
+    fn floor(size: i32) -> HashMap<IVec3, Block> {
+        let mut blocks = HashMap::new();
+        for x in -size..=size {
+            for z in -size..=size {
+                blocks.insert(ivec3(x, 2, z), Block::Stone);
+                blocks.insert(ivec3(x, 0, z), Block::Stone);
+            }
+        }
+        blocks
+    }
+
+    fn run(flow: &mut FluidFlow, blocks: &mut HashMap<IVec3, Block>) -> usize {
+        let mut ticks = 0;
+        while flow.tick(blocks) {
+            ticks += 1;
+            assert!(ticks < 1000, "water never settled");
+        }
+        ticks
+    }
+
+    #[test]
+    fn falls_then_spreads_seven_blocks() {
+        let mut blocks = floor(12);
+        let mut flow = FluidFlow::new(vec![ivec3(0, 7, 0)]);
+        run(&mut flow, &mut blocks);
+
+        for y in 3..7 {
+            assert_eq!(blocks.get_block(ivec3(0, y, 0)), Block::FlowingWater { level: FALLING });
+            assert_eq!(blocks.get_block(ivec3(1, y, 0)), Block::FlowingWater { level: FALLING });
+        }
+        // the source spreads once the water below it is falling, but that water doesn't spread any further
+        assert_eq!(blocks.get_block(ivec3(1, 7, 0)), Block::FlowingWater { level: 1 });
+        assert!(!flow.contains(ivec3(2, 7, 0)));
+        assert!(!flow.contains(ivec3(2, 4, 0)));
+
+        assert_eq!(blocks.get_block(ivec3(8, 3, 0)), Block::FlowingWater { level: 7 });
+        assert!(!flow.contains(ivec3(9, 3, 0)));
+    }
+
+    #[test]
+    fn flows_towards_the_nearest_drop() {
+        let mut blocks = floor(12);
+        blocks.remove(&ivec3(2, 2, 0));
+        let mut flow = FluidFlow::new(vec![ivec3(0, 3, 0)]);
+        run(&mut flow, &mut blocks);
+
+        assert!(flow.contains(ivec3(1, 3, 0)));
+        assert!(flow.contains(ivec3(2, 2, 0)));
+        assert!(!flow.contains(ivec3(-1, 3, 0)));
+        assert!(!flow.contains(ivec3(0, 3, 1)));
+    }
+
+    #[test]
+    fn same_blocks_same_flow() {
+        let mut results = Vec::new();
+        for _ in 0..2 {
+            let mut blocks = floor(12);
+            blocks.remove(&ivec3(3, 2, 2));
+            blocks.remove(&ivec3(-3, 2, -2));
+            blocks.insert(ivec3(1, 3, 0), Block::Stone);
+            let mut flow = FluidFlow::new(vec![ivec3(0, 5, 0)]);
+            let ticks = run(&mut flow, &mut blocks);
+
+            let mut water = flow.water.into_iter().collect::<Vec<(IVec3, u8)>>();
+            water.sort_by_key(|(position, _)| (position.x, position.y, position.z));
+            results.push((ticks, water));
+        }
+        assert_eq!(results[0], results[1]);
+    }
+
+    #[test]
+    fn clear_removes_placed_water() {
+        let mut blocks = floor(12);
+        let mut flow = FluidFlow::new(vec![ivec3(0, 3, 0)]);
+        run(&mut flow, &mut blocks);
+        flow.clear(&mut blocks);
+        assert_eq!(flow.water().count(), 0);
+        assert!(blocks.values().all(|block| matches!(block, Block::Stone | Block::Air)));
+    }
+}
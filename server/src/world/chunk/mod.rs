@@ -1,4 +1,5 @@
 pub mod chunk;
 pub mod chunk_grid;
+pub mod fluid;
 
 pub use chunk::*;
@@ -148,6 +148,35 @@ impl<W: WorldExtension + 'static> World<W> {
                 chunk.write_spawn_entities(player);
             });
 
+        let new_player_data = [PlayerData {
+            ping: 0,
+            game_mode: 0,
+            profile: &player.profile.clone(),
+            display_name: None,
+        }];
+        for other in self.players_mut() {
+            other.write_packet(&PlayerListItem {
+                action: VarInt(0),
+                players: &new_player_data,
+            });
+            player.write_packet(&PlayerListItem {
+                action: VarInt(0),
+                players: &[PlayerData {
+                    ping: 0,
+                    game_mode: 0,
+                    profile: &other.profile,
+                    display_name: None,
+                }],
+            });
+        }
+
+        self.for_each_player_in_view(player.position, |other| {
+            if other.client_id != client_id {
+                other.enter_player_view(player);
+                player.enter_player_view(other);
+            }
+        });
+
         player.flush_packets();
 
         let index = self.players.len();
@@ -259,6 +288,19 @@ impl<W: WorldExtension + 'static> World<W> {
                 chunk.remove_player(client_id)
             }
 
+            self.for_each_player_in_view(player.position, |other| {
+                player.leave_player_view(other);
+            });
+            self.write_global_packet(&PlayerListItem {
+                action: VarInt(4),
+                players: &[PlayerData {
+                    ping: 0,
+                    game_mode: 0,
+                    profile: &player.profile,
+                    display_name: None,
+                }],
+            });
+
             assert_eq!(Rc::strong_count(&player_rc), 1, "player leaked")
         }
     }
@@ -267,6 +309,41 @@ impl<W: WorldExtension + 'static> World<W> {
         self.entities_for_removal.push(entity);
     }
 
+    /// removes every entity right away, instead of waiting for the next tick
+    pub fn clear_entities(&mut self) {
+        let mut packet_destroy_entities = DestroyEntites {
+            entities: vec![]
+        };
+        for chunk in self.chunk_grid.chunks.iter_mut() {
+            for id in chunk.entities.drain() {
+                let entity = self.entities.get_entity(id);
+                if let Some(mc_entity) = entity.get::<MinecraftEntity<W>>() {
+                    (mc_entity.destroy)(mc_entity, &entity, &mut packet_destroy_entities);
+                }
+            }
+        }
+        self.entities_for_removal.clear();
+        self.entities.clear();
+
+        for player in self.players_mut() {
+            player.write_packet(&packet_destroy_entities);
+        }
+    }
+
+    /// sends every player the chunks around them again,
+    /// used after blocks have been changed without block change packets
+    pub fn resend_chunks(&mut self) {
+        for player_rc in self.players.iter() {
+            let player = unsafe { &mut *player_rc.get() };
+            let (chunk_x, chunk_z) = get_chunk_position(player.position);
+
+            self.chunk_grid
+                .for_each_in_view(chunk_x, chunk_z, VIEW_DISTANCE + 1, |chunk, x, z| {
+                    chunk.write_chunk_data(x, z, true, &mut player.packet_buffer);
+                });
+        }
+    }
+
     pub fn tick(&mut self) {
         // tick extension
         W::tick(self);
@@ -335,6 +412,24 @@ impl<W: WorldExtension + 'static> World<W> {
         }
     }
 
+    // view distance is the same both ways,
+    // so this is also every player the position can be seen by
+    pub fn for_each_player_in_view<F>(&mut self, position: DVec3, mut callback: F)
+    where
+        F: FnMut(&mut Player<W::Player>)
+    {
+        let (chunk_x, chunk_z) = get_chunk_position(position);
+        for x in chunk_x - VIEW_DISTANCE..=chunk_x + VIEW_DISTANCE {
+            for z in chunk_z - VIEW_DISTANCE..=chunk_z + VIEW_DISTANCE {
+                if let Some(chunk) = self.chunk_grid.get_chunk_mut(x, z) {
+                    for player in chunk.players() {
+                        callback(player)
+                    }
+                }
+            }
+        }
+    }
+
     pub fn players(&self) -> impl Iterator<Item = &Player<W::Player>> {
         self.players.iter().map(|it| unsafe { &*it.get() })
     }
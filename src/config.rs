@@ -0,0 +1,361 @@
+use crate::dungeon::door::door_positions::DOOR_POSITIONS;
+use crate::dungeon::floor::Floor;
+use crate::dungeon::items::aspect_of_the_void::AspectOfTheVoid;
+use crate::dungeon::items::dungeon_items::DungeonItems;
+use crate::dungeon::items::ender_pearl::EnderPearl;
+use crate::dungeon::items::hyperion::Hyperion;
+use crate::dungeon::items::pickaxe::Pickaxe;
+use crate::dungeon::items::spirit_sceptre::SpiritSceptre;
+use crate::dungeon::items::superboom::SuperboomTNT;
+use crate::dungeon::items::tactical_insertion::TacticalInsertion;
+use anyhow::{bail, Context};
+use base64::Engine;
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DEFAULT_CONFIG_PATH: &str = "config.json";
+
+// the last hotbar slot is reserved for the skyblock menu and map
+const MAX_LOADOUT_SIZE: usize = 8;
+// room ids for the 6x6 grid, then a character for each door
+const LAYOUT_LENGTH: usize = 72 + DOOR_POSITIONS.len();
+// hypixel caps speed at this
+const MAX_SPEED: u32 = 500;
+
+const USAGE: &str = "\
+usage: RustClear [options]
+
+options:
+  --config <path>         config file to load, defaults to config.json if it exists
+  --address <ip>          address to listen on
+  --port <port>           port to listen on
+  --max-players <amount>  max amount of players that can join
+  --motd <text>           text shown in the server list
+  --favicon <path>        64x64 png shown in the server list
+  --seed <seed|random>    seed used to pick the first dungeon layout
+  --floor <floor>         floor used for score, E or F1 to F7
+  --layouts <path>        file with dungeon layouts, one per line, generated if not set
+  --speed <speed>         player speed, like the skyblock stat
+  --help                  shows this";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// the loaded config, panics if called before it has been loaded
+pub fn config() -> &'static Config {
+    CONFIG.get().expect("config should be loaded at startup")
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub address: String,
+    pub port: u16,
+    pub max_players: u32,
+    // shows the version if not set
+    pub motd: Option<String>,
+    pub favicon: Option<PathBuf>,
+    // random if not set, rerolls after a run are always random
+    pub seed: Option<u64>,
+    pub floor: Floor,
+    // layouts are generated if not set
+    pub layouts: Option<PathBuf>,
+    pub speed: u32,
+    pub loadout: Vec<Option<LoadoutItem>>,
+    // shows the path through ice fill with particles, for practice
+    pub ice_fill_hints: bool,
+    // mobs the watcher spawns before blood can be cleared
+    pub blood_mobs: u32,
+
+    #[serde(skip)]
+    favicon_data: String,
+    #[serde(skip)]
+    dungeon_layouts: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1".to_string(),
+            port: 4972,
+            max_players: 5,
+            motd: None,
+            favicon: None,
+            seed: None,
+            floor: Floor::default(),
+            layouts: None,
+            speed: 500,
+            loadout: vec![
+                Some(LoadoutItem::Hyperion),
+                Some(LoadoutItem::AspectOfTheVoid),
+                Some(LoadoutItem::EnderPearl),
+                Some(LoadoutItem::Pickaxe),
+                Some(LoadoutItem::SuperboomTnt),
+                Some(LoadoutItem::SpiritSceptre),
+                Some(LoadoutItem::TacticalInsertion),
+            ],
+            ice_fill_hints: false,
+            blood_mobs: 12,
+            favicon_data: String::new(),
+            dungeon_layouts: Vec::new(),
+        }
+    }
+}
+
+/// items that can be put in the starting hotbar
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadoutItem {
+    Hyperion,
+    AspectOfTheVoid,
+    EnderPearl,
+    Pickaxe,
+    SuperboomTnt,
+    SpiritSceptre,
+    TacticalInsertion,
+}
+
+impl From<LoadoutItem> for DungeonItems {
+    fn from(item: LoadoutItem) -> Self {
+        match item {
+            LoadoutItem::Hyperion => Hyperion.into(),
+            LoadoutItem::AspectOfTheVoid => AspectOfTheVoid.into(),
+            LoadoutItem::EnderPearl => EnderPearl.into(),
+            LoadoutItem::Pickaxe => Pickaxe.into(),
+            LoadoutItem::SuperboomTnt => SuperboomTNT.into(),
+            LoadoutItem::SpiritSceptre => SpiritSceptre.into(),
+            LoadoutItem::TacticalInsertion => TacticalInsertion.into(),
+        }
+    }
+}
+
+impl Config {
+
+    /// loads the config file, applies command line overrides on top of it, and validates it.
+    /// this can only be called once
+    pub fn init(args: impl Iterator<Item = String>) -> anyhow::Result<&'static Config> {
+        if CONFIG.set(Config::load(args)?).is_err() {
+            bail!("config has already been loaded")
+        }
+        Ok(config())
+    }
+
+    fn load(args: impl Iterator<Item = String>) -> anyhow::Result<Config> {
+        let args = args.collect::<Vec<String>>();
+
+        let config_path = match option_value(&args, "--config")? {
+            Some(path) => Some(PathBuf::from(path)),
+            None => Some(PathBuf::from(DEFAULT_CONFIG_PATH)).filter(|path| path.exists()),
+        };
+        let mut config = match config_path {
+            Some(path) => Config::from_file(&path)?,
+            None => Config::default(),
+        };
+        config.apply_args(&args)?;
+        config.validate()?;
+        config.load_files()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Config> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    fn apply_args(&mut self, args: &[String]) -> anyhow::Result<()> {
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "--help" {
+                println!("{USAGE}");
+                std::process::exit(0)
+            }
+            let Some(value) = args.next() else {
+                bail!("missing value for {arg}\n\n{USAGE}")
+            };
+            let invalid = || format!("invalid value for {arg}: {value}");
+
+            match arg.as_str() {
+                "--config" => {}
+                "--address" => self.address = value.clone(),
+                "--port" => self.port = value.parse().with_context(invalid)?,
+                "--max-players" => self.max_players = value.parse().with_context(invalid)?,
+                "--motd" => self.motd = Some(value.clone()),
+                "--favicon" => self.favicon = Some(PathBuf::from(value)),
+                "--seed" => self.seed = match value.as_str() {
+                    "random" => None,
+                    _ => Some(value.parse().with_context(invalid)?),
+                },
+                "--floor" => self.floor = Floor::try_from(value.clone()).with_context(invalid)?,
+                "--layouts" => self.layouts = Some(PathBuf::from(value)),
+                "--speed" => self.speed = value.parse().with_context(invalid)?,
+                _ => bail!("unknown option {arg}\n\n{USAGE}"),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.address.parse::<IpAddr>().is_err() {
+            bail!("invalid config: address \"{}\" isn't a valid ip address", self.address)
+        }
+        if self.port == 0 {
+            bail!("invalid config: port can't be 0")
+        }
+        if self.max_players == 0 {
+            bail!("invalid config: max_players has to be at least 1")
+        }
+        if self.speed == 0 || self.speed > MAX_SPEED {
+            bail!("invalid config: speed has to be between 1 and {MAX_SPEED}, got {}", self.speed)
+        }
+        if self.blood_mobs == 0 {
+            bail!("invalid config: blood_mobs has to be at least 1")
+        }
+        if self.loadout.len() > MAX_LOADOUT_SIZE {
+            bail!(
+                "invalid config: loadout can have at most {MAX_LOADOUT_SIZE} items, got {}",
+                self.loadout.len()
+            )
+        }
+        Ok(())
+    }
+
+    // reads the favicon and layouts, so problems with them show up at startup
+    fn load_files(&mut self) -> anyhow::Result<()> {
+        if let Some(path) = &self.favicon {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("failed to read favicon {}", path.display()))?;
+            validate_favicon(&bytes)
+                .with_context(|| format!("invalid favicon {}", path.display()))?;
+            self.favicon_data = base64::engine::general_purpose::STANDARD.encode(bytes);
+        }
+
+        if let Some(path) = &self.layouts {
+            let layouts = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read layouts file {}", path.display()))?;
+            self.dungeon_layouts = parse_layouts(&layouts)
+                .with_context(|| format!("invalid layouts file {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.address, self.port)
+    }
+
+    /// base64 encoded favicon, empty if there isn't one
+    pub fn favicon_data(&self) -> &str {
+        &self.favicon_data
+    }
+
+    /// empty if layouts should be generated
+    pub fn dungeon_layouts(&self) -> &[String] {
+        &self.dungeon_layouts
+    }
+
+    /// the speed stat converted to the walk speed minecraft uses
+    pub fn walk_speed(&self) -> f32 {
+        self.speed as f32 * 0.001
+    }
+}
+
+// finds the value for an option without validating anything else
+fn option_value<'a>(args: &'a [String], option: &str) -> anyhow::Result<Option<&'a str>> {
+    let Some(index) = args.iter().position(|arg| arg == option) else {
+        return Ok(None)
+    };
+    match args.get(index + 1) {
+        Some(value) => Ok(Some(value)),
+        None => bail!("missing value for {option}\n\n{USAGE}"),
+    }
+}
+
+fn validate_favicon(bytes: &[u8]) -> anyhow::Result<()> {
+    const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    if bytes.len() < 24 || bytes[..8] != PNG_SIGNATURE {
+        bail!("favicon has to be a png")
+    }
+    // width and height are the first thing in the header chunk
+    let width = u32::from_be_bytes(bytes[16..20].try_into()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into()?);
+    if width != 64 || height != 64 {
+        bail!("favicon has to be 64x64, got {width}x{height}")
+    }
+    Ok(())
+}
+
+fn parse_layouts(contents: &str) -> anyhow::Result<Vec<String>> {
+    let mut layouts = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.len() < LAYOUT_LENGTH || !line.is_ascii() {
+            bail!("layout on line {} should be {LAYOUT_LENGTH} characters long", index + 1)
+        }
+        layouts.push(line.to_string());
+    }
+    if layouts.is_empty() {
+        bail!("there are no layouts")
+    }
+    Ok(layouts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn args_override_config() {
+        let mut config: Config = serde_json::from_str(r#"{ "port": 25565, "seed": 5 }"#).unwrap();
+        config.apply_args(&args(&["--port", "25566", "--seed", "random", "--floor", "f5"])).unwrap();
+
+        assert_eq!(config.port, 25566);
+        assert_eq!(config.seed, None);
+        assert_eq!(config.floor, Floor::F5);
+        assert_eq!(config.address(), "127.0.0.1:25566");
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        assert!(serde_json::from_str::<Config>(r#"{ "prot": 1 }"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{ "loadout": ["bonemerang"] }"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{ "floor": "M7" }"#).is_err());
+
+        let config = Config { max_players: 0, ..Config::default() };
+        assert!(config.validate().is_err());
+        let config = Config { address: "localhost".to_string(), ..Config::default() };
+        assert!(config.validate().is_err());
+        let config = Config { loadout: vec![None; 9], ..Config::default() };
+        assert!(config.validate().is_err());
+        let config = Config { blood_mobs: 0, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let mut config = Config::default();
+        assert!(config.apply_args(&args(&["--port"])).is_err());
+        assert!(config.apply_args(&args(&["--speed", "fast"])).is_err());
+        assert!(config.apply_args(&args(&["--sped", "400"])).is_err());
+    }
+
+    #[test]
+    fn favicon_has_to_be_64x64_png() {
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13, b'I', b'H', b'D', b'R'];
+        png.extend(64u32.to_be_bytes());
+        png.extend(64u32.to_be_bytes());
+        assert!(validate_favicon(&png).is_ok());
+
+        png[23] = 32;
+        assert!(validate_favicon(&png).is_err());
+        assert!(validate_favicon(b"not a png").is_err());
+    }
+}
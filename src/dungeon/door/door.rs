@@ -162,8 +162,9 @@ impl Door {
                     (
                         MovingBlockBehaviour {
                             block: ivec3(x, y, z),
-                            remove_block_in_tick: 20,
-                            difference: -0.25,
+                            replace_with: Block::Air,
+                            replace_in_tick: 20,
+                            difference: dvec3(0.0, -0.25, 0.0),
                         },
                         Lifetime {
                             ticks: 20
@@ -0,0 +1,243 @@
+use crate::dungeon::door::door::DoorType;
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::block_appearance::BlockAppearance;
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_data::RoomData;
+use bevy_ecs::prelude::Component;
+use glam::{dvec3, ivec3, IVec3};
+use server::block::block_parameter::BlockColor;
+use server::block::Block;
+use server::constants::Sound;
+use server::entity::components::EntityBehaviour;
+use server::entity::entity::MinecraftEntity;
+use server::network::protocol::play::clientbound::Chat;
+use server::types::aabb::AABB;
+use server::World;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyType {
+    Wither,
+    Blood,
+}
+
+impl KeyType {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            KeyType::Wither => "§8Wither Key",
+            KeyType::Blood => "§cBlood Key",
+        }
+    }
+
+    // same as the door it opens
+    const fn block(&self) -> Block {
+        match self {
+            KeyType::Wither => Block::CoalBlock,
+            KeyType::Blood => Block::StainedHardenedClay { color: BlockColor::Red },
+        }
+    }
+}
+
+/// floats in place until someone walks into it
+#[derive(Component)]
+pub struct KeyBehaviour {
+    pub key: KeyType,
+}
+
+impl EntityBehaviour<Dungeon> for KeyBehaviour {
+    fn tick(entity: &mut MinecraftEntity<Dungeon>, component: &mut Self) {
+        let world = entity.world_mut();
+        if !world.has_started() {
+            return;
+        }
+        let aabb = AABB::from_width_height(1.0, 1.0).offset(entity.position);
+        let Some(player) = world.players_mut().find(|player| !player.is_dead && player.collision_aabb().intersects(&aabb)) else {
+            return;
+        };
+
+        let message = format!("§7{} §ehas obtained {}§e!", player.profile.username, component.key.name());
+        player.play_sound(Sound::EndermanPortal, 1.0, 1.0);
+        match component.key {
+            KeyType::Wither => world.wither_key_count += 1,
+            KeyType::Blood => world.blood_key_count += 1,
+        }
+        world.write_global_packet(&Chat::new(&message));
+        entity.destroy();
+    }
+}
+
+// open floor in the room, closest to the middle first
+fn floor_spots(room_data: &RoomData) -> Vec<IVec3> {
+    let mut spots = Vec::new();
+    for x in 0..room_data.width {
+        for z in 0..room_data.length {
+            for y in room_data.bottom + 1..room_data.bottom + room_data.height - 1 {
+                let is_floor = room_data.get_block(ivec3(x, y - 1, z)) != Block::Air
+                    && room_data.get_block(ivec3(x, y, z)) == Block::Air
+                    && room_data.get_block(ivec3(x, y + 1, z)) == Block::Air;
+                if is_floor {
+                    spots.push(ivec3(x, y, z));
+                    break;
+                }
+            }
+        }
+    }
+    let (center_x, center_z) = (room_data.width / 2, room_data.length / 2);
+    spots.sort_by_key(|spot| (spot.x - center_x).pow(2) + (spot.z - center_z).pow(2));
+    spots
+}
+
+pub fn spawn_key(world: &mut World<Dungeon>, room: &Room, key: KeyType, index: usize) {
+    // keys in the same room take the next closest spot,
+    // rooms without block data just get the middle of the first segment
+    let spot = floor_spots(&room.data).get(index).copied().unwrap_or(ivec3(15 + index as i32, 70, 15));
+    let position = room.get_world_block_position(spot).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+    world.spawn_entity(
+        position,
+        0.0,
+        0.0,
+        BlockAppearance {
+            block: key.block(),
+        },
+        KeyBehaviour {
+            key,
+        },
+    );
+}
+
+/// puts a wither key in the room in front of every wither door,
+/// and the blood key in the room behind the wither door furthest from the entrance
+pub fn place_keys(rooms: &[Rc<RefCell<Room>>], entrance: &Rc<RefCell<Room>>) {
+    let index_of = |room: &Rc<RefCell<Room>>| rooms.iter().position(|other| Rc::ptr_eq(other, room)).unwrap();
+
+    // how many doors away from the entrance each room is
+    let mut distances = vec![usize::MAX; rooms.len()];
+    let mut queue = VecDeque::from([index_of(entrance)]);
+    distances[index_of(entrance)] = 0;
+    while let Some(index) = queue.pop_front() {
+        for neighbour in rooms[index].borrow().neighbours() {
+            let other = index_of(&neighbour.room);
+            if distances[other] == usize::MAX {
+                distances[other] = distances[index] + 1;
+                queue.push_back(other);
+            }
+        }
+    }
+
+    let mut keys = Vec::new();
+    let mut furthest_wither = None;
+    let mut blood_door_room = None;
+    for (index, room) in rooms.iter().enumerate() {
+        for neighbour in room.borrow().neighbours() {
+            let other = index_of(&neighbour.room);
+            // every door is seen from both sides, only count it from the one closer to the entrance
+            if (distances[other], other) < (distances[index], index) {
+                continue;
+            }
+            match neighbour.door.borrow().get_type() {
+                DoorType::Wither => {
+                    keys.push((index, KeyType::Wither));
+                    if furthest_wither.is_none_or(|(distance, _)| distances[other] > distance) {
+                        furthest_wither = Some((distances[other], other));
+                    }
+                }
+                DoorType::Blood => blood_door_room = Some(index),
+                _ => {}
+            }
+        }
+    }
+    if let Some(index) = furthest_wither.map(|(_, index)| index).or(blood_door_room) {
+        keys.push((index, KeyType::Blood));
+    }
+
+    for (index, key) in keys {
+        rooms[index].borrow_mut().keys.push(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::layout_generator::generate_layout;
+    use crate::dungeon::seeded_rng::SeededRng;
+    use server::utils::hasher::deterministic_hasher::DeterministicHashMap;
+
+    #[test]
+    fn keys_go_on_open_floor() {
+        // a 5x5 floor with a pillar in the middle
+        let mut block_data = vec![Block::Air; 5 * 5 * 4];
+        block_data[..25].fill(Block::Bedrock);
+        for y in 0..4 {
+            block_data[2 + 2 * 5 + y * 25] = Block::Bedrock;
+        }
+        let room_data = RoomData {
+            width: 5,
+            length: 5,
+            height: 4,
+            block_data,
+            ..RoomData::dummy()
+        };
+        let spots = floor_spots(&room_data);
+        assert_eq!(spots.len(), 24);
+        assert!(spots.iter().all(|spot| spot.y == 69));
+        assert!(!spots.contains(&ivec3(2, 69, 2)));
+        assert_eq!((spots[0] - ivec3(2, 69, 2)).abs().element_sum(), 1);
+    }
+
+    // walks through the dungeon picking up every key it can reach, the blood door should always be opened
+    #[test]
+    fn keys_can_be_picked_up_in_order() {
+        for seed in 0..100 {
+            SeededRng::set_seed(seed);
+            let dungeon = Dungeon::from_string(&generate_layout().unwrap(), &DeterministicHashMap::default()).unwrap();
+            let wither_doors = dungeon.doors.iter().filter(|door| *door.borrow().get_type() == DoorType::Wither).count();
+            let keys = dungeon.rooms.iter().flat_map(|room| room.borrow().keys.clone()).collect::<Vec<_>>();
+            assert_eq!(keys.iter().filter(|key| **key == KeyType::Wither).count(), wither_doors, "seed {seed}");
+            assert_eq!(keys.iter().filter(|key| **key == KeyType::Blood).count(), 1, "seed {seed}");
+
+            let mut reached = vec![dungeon.entrance_room()];
+            let mut opened = 0;
+            let (mut wither_keys, mut blood_keys) = (0, 0);
+            let mut blood_opened = false;
+            loop {
+                let mut changed = false;
+                for room in reached.clone() {
+                    for key in room.borrow_mut().keys.drain(..) {
+                        match key {
+                            KeyType::Wither => wither_keys += 1,
+                            KeyType::Blood => blood_keys += 1,
+                        }
+                    }
+                    for neighbour in room.borrow().neighbours() {
+                        if reached.iter().any(|other| Rc::ptr_eq(other, &neighbour.room)) {
+                            continue;
+                        }
+                        let can_open = match neighbour.door.borrow().get_type() {
+                            DoorType::Wither if wither_keys > opened => {
+                                opened += 1;
+                                true
+                            }
+                            DoorType::Wither => false,
+                            DoorType::Blood if blood_keys > 0 => {
+                                blood_opened = true;
+                                true
+                            }
+                            DoorType::Blood => false,
+                            _ => true,
+                        };
+                        if can_open {
+                            reached.push(neighbour.room.clone());
+                            changed = true;
+                        }
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+            assert!(blood_opened, "seed {seed} can't reach the blood room");
+        }
+    }
+}
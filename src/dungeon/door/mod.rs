@@ -1,3 +1,4 @@
 pub mod door;
-pub(super) mod door_positions;
-pub mod sound_emitter;
\ No newline at end of file
+pub(crate) mod door_positions;
+pub mod sound_emitter;
+pub mod key;
\ No newline at end of file
@@ -1,28 +1,31 @@
+use crate::config::config;
 use crate::dungeon::door::door::{Door, DoorType};
 use crate::dungeon::door::door_positions::DOOR_POSITIONS;
+use crate::dungeon::door::key::place_keys;
 use crate::dungeon::dungeon_player::DungeonPlayer;
-use crate::dungeon::items::aspect_of_the_void::AspectOfTheVoid;
-use crate::dungeon::items::ender_pearl::EnderPearl;
-use crate::dungeon::items::hyperion::Hyperion;
+use crate::dungeon::entities::health::{apply_queued_damage, Damage, DamageTarget};
 use crate::dungeon::items::magical_map::MagicalMap;
-use crate::dungeon::items::pickaxe::Pickaxe;
 use crate::dungeon::items::skyblock_menu::SkyblockMenu;
-use crate::dungeon::items::spirit_sceptre::SpiritSceptre;
-use crate::dungeon::items::superboom::SuperboomTNT;
-use crate::dungeon::items::tactical_insertion::TacticalInsertion;
+use crate::dungeon::floor::Floor;
 use crate::dungeon::map::DungeonMap;
 use crate::dungeon::room::room::{Room, RoomNeighbour, RoomSegment, RoomStatus};
 use crate::dungeon::room::room_data::{get_random_data_with_type, RoomData, RoomShape, RoomType};
-use crate::MortMenu;
+use crate::dungeon::score::{calculate_score, Score, ScoreStats};
+use crate::dungeon::seeded_rng::SeededRng;
+use crate::{generate_dungeon, load_dungeon, spawn_mort, MortMenu};
 use anyhow::bail;
+use bevy_ecs::entity::Entity;
 use glam::{ivec3, DVec3, IVec2};
+use indoc::formatdoc;
 use server::block::block_parameter::Axis;
 use server::block::rotatable::Rotate;
 use server::commands::command_parse::GreedyString;
 use server::constants::Gamemode;
 use server::inventory::menu::OpenContainer;
 use server::network::binary::var_int::VarInt;
-use server::network::protocol::play::clientbound::{Chat, EntityProperties, PlayerAbilities, PositionLook, Relative};
+use server::network::internal_packets::NetworkThreadMessage;
+use server::network::packets::packet_buffer::PacketBuffer;
+use server::network::protocol::play::clientbound::{Chat, Disconnect, EntityProperties, PlayerAbilities, PositionLook, Relative};
 use server::player::attribute::{Attribute, AttributeMap, AttributeModifier};
 use server::types::aabb::AABB;
 use server::types::chat_component::ChatComponent;
@@ -34,10 +37,18 @@ use uuid::Uuid;
 
 pub const DUNGEON_ORIGIN: IVec2 = IVec2::new(-200, -200);
 
+// how long players stay in a finished run before going back to the lobby
+const LOBBY_DELAY_TICKS: usize = 200;
+// runs can't go on forever
+const RUN_TIME_LIMIT_TICKS: usize = 60 * 60 * 20;
+
 pub enum DungeonState {
     NotStarted,
     Starting { starts_in_ticks: usize },
-    Started { ticks: usize }
+    Started { ticks: usize },
+    // ticks are frozen at when the run ended
+    Finished { ticks: usize, lobby_in_ticks: usize },
+    Failed { ticks: usize, lobby_in_ticks: usize },
 }
 
 pub struct Dungeon {
@@ -48,12 +59,26 @@ pub struct Dungeon {
 
     pub state: DungeonState,
     pub map: DungeonMap,
+    pub floor: Floor,
 
     // there can be only 1 blood key in hypixel,
     // but what if we ever want to do some fun stuff with more than 1
     pub blood_key_count: usize,
     pub wither_key_count: usize,
     pub cleared_percent: i32,
+
+    pub deaths: usize,
+    pub secrets_found: usize,
+    pub secrets_total: usize,
+    pub crypts_blown: usize,
+    pub mimic_killed: bool,
+    pub prince_killed: bool,
+    // ticks into the run when the blood door was opened
+    pub blood_opened_at: Option<usize>,
+    // highest score that has been announced in chat
+    announced_score: i32,
+
+    pub damage_queue: Vec<Damage>,
 }
 
 impl WorldExtension for Dungeon {
@@ -61,6 +86,10 @@ impl WorldExtension for Dungeon {
     type Player = DungeonPlayer;
 
     fn tick(world: &mut World<Self>) {
+        for entity in apply_queued_damage(world) {
+            Dungeon::on_entity_death(world, entity)
+        }
+
         let dungeon = &mut world.extension;
 
         match &mut dungeon.state {
@@ -84,6 +113,12 @@ impl WorldExtension for Dungeon {
             }
             DungeonState::Started { ticks } => {
                 *ticks += 1;
+                let ticks = *ticks;
+
+                if ticks >= RUN_TIME_LIMIT_TICKS {
+                    Dungeon::end_run(world, false);
+                    return;
+                }
 
                 for player_rc in world.players.iter_mut() {
                     let player = unsafe { &mut *player_rc.get() };
@@ -123,6 +158,8 @@ impl WorldExtension for Dungeon {
                 // clear percent is based on segments and not
                 let mut total_segments = 0;
                 let mut complete_segments = 0;
+                // failed puzzles can't be cleared anymore, so they don't hold the run up
+                let mut rooms_left = 0;
 
                 for index in 0..world.rooms.len() {
                     let room_rc = world.rooms[index].clone();
@@ -135,35 +172,58 @@ impl WorldExtension for Dungeon {
                     room.tick(world);
 
                     total_segments += room.segments.len();
+                    if !matches!(room.status, RoomStatus::Complete | RoomStatus::Failed) {
+                        rooms_left += 1;
+                    }
                     if matches!(room.status, RoomStatus::Complete) {
                         complete_segments += room.segments.len();
+                        if room.cleared_at.is_none() {
+                            room.cleared_at = Some(ticks);
+                        }
                     }
                 }
 
                 let p = complete_segments as f32 / total_segments as f32;
                 world.cleared_percent = ((p * 100.0).round() as i32).clamp(0, 100);
 
+                Dungeon::announce_score(world);
+
                 if let Some(packet) = world.extension.map.get_packet() {
                     for player in world.players_mut() {
                         player.write_packet(&packet)
                     }
                 }
+
+                if rooms_left == 0 {
+                    Dungeon::end_run(world, true);
+                }
+            }
+            DungeonState::Finished { lobby_in_ticks, .. } | DungeonState::Failed { lobby_in_ticks, .. } => {
+                *lobby_in_ticks -= 1;
+                if *lobby_in_ticks == 0 {
+                    Dungeon::return_to_lobby(world)
+                }
             }
             _ => {}
         }
     }
 
     fn on_player_join(world: &mut World<Self>, profile: GameProfile, client_id: ClientId) {
-        let entrance = world.extension.entrance_room().clone();
-        let entrance = entrance.borrow();
+        if world.players.len() >= config().max_players as usize {
+            let mut packet_buffer = PacketBuffer::new();
+            packet_buffer.write_packet(&Disconnect {
+                reason: ChatComponent::new("§cThe server is full!"),
+            });
+            let _ = world.network_tx.send(packet_buffer.get_packet_message(client_id));
+            let _ = world.network_tx.send(NetworkThreadMessage::DisconnectClient { client_id });
+            return;
+        }
+
+        let (position, yaw) = world.spawn_position();
 
-        let mut position = entrance.get_world_block_position(ivec3(15, 72, 18)).as_dvec3();
-        position.x += 0.5;
-        position.z += 0.5;
-        
         let player = world.spawn_player(
             position,
-            180.0.rotate(entrance.rotation),
+            yaw,
             0.0,
             profile,
             client_id,
@@ -171,7 +231,7 @@ impl WorldExtension for Dungeon {
             DungeonPlayer::default()
         );
 
-        let speed: f32 = 500.0 * 0.001;
+        let speed = config().walk_speed();
 
         let mut attributes = AttributeMap::new();
         attributes.insert(Attribute::MovementSpeed, speed as f64);
@@ -196,13 +256,9 @@ impl WorldExtension for Dungeon {
 
         player.extension.sidebar.write_init_packets(&mut player.packet_buffer);
         
-        player.inventory.set_slot(36, Some(Hyperion.into()));
-        player.inventory.set_slot(37, Some(AspectOfTheVoid.into()));
-        player.inventory.set_slot(38, Some(EnderPearl.into()));
-        player.inventory.set_slot(39, Some(Pickaxe.into()));
-        player.inventory.set_slot(40, Some(SuperboomTNT.into()));
-        player.inventory.set_slot(41, Some(SpiritSceptre.into()));
-        player.inventory.set_slot(42, Some(TacticalInsertion.into()));
+        for (index, item) in config().loadout.iter().enumerate() {
+            player.inventory.set_slot(36 + index, item.map(Into::into));
+        }
         player.inventory.set_slot(44, Some(SkyblockMenu.into()));
         player.sync_inventory();
 
@@ -235,12 +291,28 @@ impl WorldExtension for Dungeon {
             })
         );
 
+        player.command_dispatcher_mut().register_command(
+            command!("reroll", |player: &mut Player<DungeonPlayer>, seed: Option<u64>| {
+                if !player.world().in_lobby() {
+                    player.send_message("§cYou can't reroll during a run!");
+                    return Ok(());
+                }
+                let seed = seed.unwrap_or_else(rand::random);
+                Dungeon::reroll(player.world_mut(), seed)?;
+                player.world_mut().write_global_packet(&Chat {
+                    component: ChatComponent::new(format!("§aRerolled the dungeon with seed §e{seed}")),
+                    chat_type: 0,
+                });
+            })
+        );
+
         player.command_dispatcher_mut().register_command(
             command!("roomdata", |player: &mut Player<DungeonPlayer>| {
                 if let Some(room) = player.get_current_room() {
                     let room = room.borrow();
                     // add more data when its needed
                     player.send_message(&format!("rotation {:?}", room.rotation));
+                    player.send_message(&format!("secrets {}/{}", room.secrets_found(), room.secrets.len()));
                 }
             })
         );
@@ -262,6 +334,30 @@ impl Dungeon {
         matches!(self.state, DungeonState::Started { .. })
     }
 
+    pub fn in_lobby(&self) -> bool {
+        matches!(self.state, DungeonState::NotStarted | DungeonState::Starting { .. })
+    }
+
+    /// how long the current run has gone on for, this stops once the run ends
+    pub fn run_ticks(&self) -> usize {
+        match self.state {
+            DungeonState::Started { ticks }
+            | DungeonState::Finished { ticks, .. }
+            | DungeonState::Failed { ticks, .. } => ticks,
+            _ => 0,
+        }
+    }
+
+    pub fn spawn_position(&self) -> (DVec3, f32) {
+        let entrance = self.entrance_room();
+        let entrance = entrance.borrow();
+
+        let mut position = entrance.get_world_block_position(ivec3(15, 72, 18)).as_dvec3();
+        position.x += 0.5;
+        position.z += 0.5;
+        (position, 180.0.rotate(entrance.rotation))
+    }
+
     pub fn start_dungeon(world: &mut World<Self>) {
         for player in world.players_mut() {
             if let OpenContainer::Menu(_) = player.get_container() {
@@ -274,6 +370,7 @@ impl Dungeon {
         {
             let entrance_room = world.entrance_room();
             entrance_room.borrow_mut().status = RoomStatus::Complete;
+            entrance_room.borrow_mut().spawn_keys(world);
             world.map.draw_room(&entrance_room.borrow());
         }
 
@@ -283,8 +380,156 @@ impl Dungeon {
         }
     }
 
+    /// ends the run, either from completing it or failing it.
+    /// after a short delay, everyone gets sent back to the lobby
+    pub fn end_run(world: &mut World<Self>, completed: bool) {
+        if !world.has_started() {
+            return;
+        }
+        let ticks = world.run_ticks();
+        world.state = if completed {
+            DungeonState::Finished { ticks, lobby_in_ticks: LOBBY_DELAY_TICKS }
+        } else {
+            DungeonState::Failed { ticks, lobby_in_ticks: LOBBY_DELAY_TICKS }
+        };
+
+        let summary = world.run_summary(completed);
+        world.write_global_packet(&Chat {
+            component: ChatComponent::new(summary),
+            chat_type: 0,
+        });
+    }
+
+    fn run_summary(&self, completed: bool) -> String {
+        const LINE: &str = "§a§l▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬▬";
+
+        let score = self.score();
+        let result = if completed { "§a§lDUNGEON COMPLETE" } else { "§c§lDUNGEON FAILED" };
+
+        let mut summary = formatdoc! {r#"
+                {LINE}
+                              §f§lThe Catacombs §8- §e{floor}
+                                    {result}
+
+                          §fTeam Score: §a{total} §f({rank}§f)
+                          §fTime: §a{time}
+                          §fSecrets Found: §b{secrets_found}/{secrets_total}
+                          §fDeaths: §c{deaths}
+            "#,
+            floor = self.floor.name(),
+            total = score.total(),
+            rank = score.rank().display(),
+            time = format_time(self.run_ticks()),
+            secrets_found = self.secrets_found,
+            secrets_total = self.secrets_total,
+            deaths = self.deaths,
+        };
+
+        let mut splits = self.rooms.iter()
+            .map(|room| room.borrow())
+            .filter(|room| room.data.room_type != RoomType::Entrance)
+            .filter_map(|room| {
+                let name = match room.data.room_type {
+                    RoomType::Blood => "Blood Clear".to_string(),
+                    _ => room.data.name.clone(),
+                };
+                Some((room.cleared_at?, name))
+            })
+            .collect::<Vec<_>>();
+        if let Some(ticks) = self.blood_opened_at {
+            splits.push((ticks, "Blood Open".to_string()));
+        }
+        splits.sort_by_key(|(ticks, _)| *ticks);
+
+        if !splits.is_empty() {
+            summary.push_str("\n          §7Room splits:\n");
+            for (ticks, name) in splits {
+                summary.push_str(&format!("          §7{name}: §a{}\n", format_time(ticks)));
+            }
+        }
+        summary.push_str(LINE);
+        summary
+    }
+
+    /// rerolls the dungeon, resets everything and brings players back to the entrance,
+    /// so a new run can be started from mort
+    pub fn return_to_lobby(world: &mut World<Self>) {
+        if let Err(error) = Dungeon::reroll(world, rand::random()) {
+            eprintln!("failed to reroll dungeon: {error}")
+        }
+        world.state = DungeonState::NotStarted;
+        world.cleared_percent = 0;
+        world.deaths = 0;
+        world.secrets_found = 0;
+        world.crypts_blown = 0;
+        world.mimic_killed = false;
+        world.prince_killed = false;
+        world.announced_score = 0;
+
+        let (position, yaw) = world.spawn_position();
+
+        for player in world.players_mut() {
+            if let Some((room_rc, _)) = player.extension.current_room.take() {
+                room_rc.borrow_mut().remove_player_ref(player.client_id);
+            }
+            player.is_ready = false;
+            DungeonPlayer::set_ghost(player, false);
+
+            player.open_container(OpenContainer::None);
+            player.inventory.set_slot(44, Some(SkyblockMenu.into()));
+            player.sync_inventory();
+
+            player.write_packet(&PositionLook {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+                yaw,
+                pitch: 0.0,
+                flags: Default::default(),
+            });
+            player.send_message("§aReturned to the lobby, talk to Mort to start a new run!");
+        }
+    }
+
+    /// replaces the dungeon with a new one generated from the seed,
+    /// connected players are sent the new chunks and moved to the new entrance
+    pub fn reroll(world: &mut World<Self>, seed: u64) -> anyhow::Result<()> {
+        SeededRng::set_seed(seed);
+        let dungeon = generate_dungeon()?;
+
+        for player in world.players_mut() {
+            if let Some((room_rc, _)) = player.extension.current_room.take() {
+                room_rc.borrow_mut().remove_player_ref(player.client_id);
+            }
+        }
+        world.clear_entities();
+        world.chunk_grid.clear_blocks();
+
+        world.extension = dungeon;
+        load_dungeon(world);
+        spawn_mort(world);
+
+        // the chunks players already have loaded, the ones around the new entrance
+        // get sent by the next player tick along with their entities
+        world.resend_chunks();
+
+        let (position, yaw) = world.spawn_position();
+        for player in world.players_mut() {
+            player.position = position;
+            player.write_packet(&PositionLook {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+                yaw,
+                pitch: 0.0,
+                flags: Default::default(),
+            });
+        }
+        Ok(())
+    }
+
     pub fn update_ready_status(world: &mut World<Self>, player: &mut Player<DungeonPlayer>) {
-        assert!(!matches!(world.state, DungeonState::Started { .. }), "tried to ready up when dungeon has already started");
+        assert!(world.in_lobby(), "tried to ready up when dungeon has already started");
 
         let is_ready = player.extension.is_ready;
         let message = format!("§7{} {}!", player.profile.username, if is_ready { "§ais now ready" } else { "§cis no longer ready" });
@@ -491,15 +736,8 @@ impl Dungeon {
             bail!("Placeholder neighbour is leaked, likely caused by invalid room layout")
         }
 
-        // temp
-        let mut wither_key_count = 0;
-
-        for door in doors.iter() {
-            let door = door.borrow();
-            if let DoorType::Wither = door.get_type() {
-                wither_key_count += 1;
-            }
-        }
+        place_keys(&rooms, &rooms[entrance_room_index]);
+        let secrets_total = rooms.iter().map(|room| room.borrow().secrets.len()).sum();
 
         let map_offset_x = (128 - (grid_max_x + 1) * 20) / 2;
         let map_offset_y = (128 - (grid_max_y + 1) * 20) / 2;
@@ -511,12 +749,84 @@ impl Dungeon {
             entrance_room_index,
             state: DungeonState::NotStarted,
             map: DungeonMap::new(map_offset_x, map_offset_y),
-            wither_key_count,
-            blood_key_count: 1,
+            floor: Floor::default(),
+            wither_key_count: 0,
+            blood_key_count: 0,
             cleared_percent: 0,
+            deaths: 0,
+            secrets_found: 0,
+            secrets_total,
+            crypts_blown: 0,
+            mimic_killed: false,
+            prince_killed: false,
+            blood_opened_at: None,
+            announced_score: 0,
+            damage_queue: Vec::new(),
+        })
+    }
+
+    pub fn score(&self) -> Score {
+        let seconds = self.run_ticks() / 20;
+        let failed_puzzles = self.rooms.iter()
+            .map(|room| room.borrow())
+            .filter(|room| room.data.room_type == RoomType::Puzzle && matches!(room.status, RoomStatus::Failed))
+            .count();
+
+        calculate_score(&ScoreStats {
+            floor: self.floor,
+            seconds: seconds as u32,
+            cleared_percent: self.cleared_percent,
+            deaths: self.deaths,
+            failed_puzzles,
+            secrets_found: self.secrets_found,
+            secrets_total: self.secrets_total,
+            crypts: self.crypts_blown,
+            mimic_killed: self.mimic_killed,
+            prince_killed: self.prince_killed,
+        })
+    }
+
+    fn announce_score(world: &mut World<Self>) {
+        const ANNOUNCEMENTS: [i32; 2] = [270, 300];
+
+        let total = world.score().total();
+        for milestone in ANNOUNCEMENTS {
+            if total >= milestone && world.announced_score < milestone {
+                world.write_global_packet(&Chat {
+                    component: ChatComponent::new(format!("§a{milestone} score reached!")),
+                    chat_type: 0,
+                });
+                world.announced_score = milestone;
+            }
+        }
+    }
+
+    pub fn damage_entity(&mut self, entity: Entity, amount: f32, knockback_from: Option<DVec3>) {
+        self.damage_queue.push(Damage {
+            target: DamageTarget::Entity(entity),
+            amount,
+            knockback_from,
         })
     }
 
+    pub fn damage_area(&mut self, center: DVec3, radius: f64, amount: f32) {
+        self.damage_queue.push(Damage {
+            target: DamageTarget::Area { center, radius },
+            amount,
+            knockback_from: None,
+        })
+    }
+
+    fn on_entity_death(world: &mut World<Self>, entity: Entity) {
+        for index in 0..world.rooms.len() {
+            let room_rc = world.rooms[index].clone();
+            let mut room = room_rc.borrow_mut();
+            if !room.is_undiscovered() {
+                room.on_entity_death(world, entity)
+            }
+        }
+    }
+
     pub fn entrance_room(&self) -> Rc<RefCell<Room>> {
         self.rooms[self.entrance_room_index].clone()
     }
@@ -535,6 +845,16 @@ impl Dungeon {
     }
 }
 
+/// formats like the sidebar timer, 05m30s
+pub fn format_time(ticks: usize) -> String {
+    let seconds = ticks / 20;
+    if seconds >= 60 {
+        format!("{:02}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{seconds:02}s")
+    }
+}
+
 fn grid_position(x: i32, z: i32) -> Option<usize> {
     if x < DUNGEON_ORIGIN.x || z < DUNGEON_ORIGIN.y {
         return None;
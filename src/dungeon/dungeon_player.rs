@@ -1,17 +1,24 @@
-use crate::dungeon::dungeon::{Dungeon, DungeonState};
+use crate::config::config;
+use crate::dungeon::dungeon::{format_time, Dungeon, DungeonState};
+use crate::dungeon::entities::dungeon_mob::MOB_HITBOX;
+use crate::dungeon::entities::health::Health;
 use crate::dungeon::items::ability::{Ability, ActiveAbility, Cooldown};
-use crate::dungeon::items::dungeon_items::{DungeonItem, DungeonItems};
+use crate::dungeon::items::dungeon_items::{DungeonItem, DungeonItems, BASE_MELEE_DAMAGE};
 use crate::dungeon::room::room::Room;
+use bevy_ecs::entity::Entity;
 use chrono::Local;
 use glam::IVec3;
 use indoc::{formatdoc, indoc};
 use server::constants::{PotionEffect, Sound};
+use server::entity::entity::MinecraftEntity;
+use server::entity::entity_metadata::EntityFlags;
 use server::inventory::item::get_item_stack;
 use server::inventory::item_stack::ItemStack;
-use server::network::protocol::play::clientbound::{AddEffect, BlockChange};
+use server::network::protocol::play::clientbound::{AddEffect, BlockChange, Chat, PlayerAbilities, PlayerListHeaderFooter, PositionLook};
 use server::network::protocol::play::serverbound::PlayerDiggingAction;
 use server::player::packet_processing::BlockInteractResult;
 use server::player::sidebar::Sidebar;
+use server::types::chat_component::ChatComponent;
 use server::types::direction::Direction3D;
 use server::{Player, PlayerExtension};
 use std::cell::{Cell, RefCell};
@@ -19,9 +26,17 @@ use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+// vanilla reach is 3, the rest is leeway for latency
+const MAX_ATTACK_REACH: f64 = 4.5;
+
+// falling below this kills you
+const VOID_LEVEL: f64 = 0.0;
+
 pub struct DungeonPlayer {
     pub sidebar: Sidebar,
     pub is_ready: bool,
+    // dead players are ghosts until the run ends
+    pub is_dead: bool,
 
     pub current_room: Option<(Rc<RefCell<Room>>, Option<usize>)>,
 
@@ -38,6 +53,7 @@ impl Default for DungeonPlayer {
         Self {
             sidebar: Sidebar::new(),
             is_ready: false,
+            is_dead: false,
             current_room: None,
             active_abilities: Cell::new(Vec::new()),
             cooldowns: Default::default(),
@@ -68,9 +84,25 @@ impl PlayerExtension for DungeonPlayer {
             });
         }
 
+        if player.position.y < VOID_LEVEL {
+            DungeonPlayer::kill(player, "fell into the void");
+            let (position, yaw) = player.world().spawn_position();
+            player.write_packet(&PositionLook {
+                x: position.x,
+                y: position.y,
+                z: position.z,
+                yaw,
+                pitch: 0.0,
+                flags: Default::default(),
+            });
+        }
+
         if player.ticks_existed.is_multiple_of(2) {
             DungeonPlayer::update_sidebar(player);
         }
+        if player.ticks_existed.is_multiple_of(20) && player.world().has_started() {
+            DungeonPlayer::update_tab_list(player);
+        }
         
         let mut abilities = player.active_abilities.take();
         abilities.retain_mut(|active| {
@@ -162,6 +194,38 @@ impl PlayerExtension for DungeonPlayer {
             DungeonItem::on_interact(&held_item, player, block);
         }
     }
+
+    fn attack(player: &mut Player<Self>, entity: Entity) {
+        if let Some(room_rc) = player.get_current_room() && player.world().has_started() {
+            Room::attack_entity(&room_rc, player, entity);
+        }
+
+        let world = player.world_mut();
+        let entity_ref = world.entities.get_entity(entity);
+        let (Some(health), Some(mc_entity)) = (
+            entity_ref.get::<Health>(),
+            entity_ref.get::<MinecraftEntity<Dungeon>>(),
+        ) else {
+            return;
+        };
+        if health.is_dead() {
+            return;
+        }
+
+        // distance to the closest point of the hitbox, not its center
+        let eye_position = player.player_eye_position();
+        let hitbox = MOB_HITBOX.offset(mc_entity.position);
+        let closest = eye_position.clamp(hitbox.min, hitbox.max);
+        if closest.distance_squared(eye_position) > MAX_ATTACK_REACH * MAX_ATTACK_REACH {
+            return;
+        }
+
+        let damage = match player.inventory.get_hotbar_slot(player.held_slot as usize) {
+            Some(item) => item.melee_damage(),
+            None => BASE_MELEE_DAMAGE,
+        };
+        world.damage_entity(entity, damage, Some(player.position));
+    }
 }
 
 impl DungeonPlayer {
@@ -193,11 +257,70 @@ impl DungeonPlayer {
         self.active_abilities.get_mut().push(active_ability)
     }
 
+    /// puts the item in the first free slot, hotbar first.
+    /// returns false if the inventory is full, dungeon items don't stack so having it already is enough
+    pub fn give_item(player: &mut Player<Self>, item: DungeonItems) -> bool {
+        let items = &player.inventory.items;
+        if items.contains(&Some(item)) {
+            return true;
+        }
+        let Some(slot) = (36..45).chain(9..36).find(|slot| items[*slot].is_none()) else {
+            return false;
+        };
+        player.inventory.set_slot(slot, Some(item));
+        player.sync_inventory();
+        true
+    }
+
     pub fn ready(player: &mut Player<Self>) {
+        if !player.world().in_lobby() {
+            return;
+        }
         player.is_ready = !player.is_ready;
         Dungeon::update_ready_status(player.world_mut(), player);
     }
 
+    /// turns the player into a ghost, if everyone is dead the run fails
+    pub fn kill(player: &mut Player<Self>, reason: &str) {
+        if player.is_dead || !player.world().has_started() {
+            return;
+        }
+        let world = player.world_mut();
+        world.deaths += 1;
+        world.write_global_packet(&Chat {
+            component: ChatComponent::new(format!("§c☠ §7{} {reason} and became a ghost.", player.profile.username)),
+            chat_type: 0,
+        });
+        DungeonPlayer::set_ghost(player, true);
+
+        if world.players().all(|player| player.is_dead) {
+            Dungeon::end_run(world, false)
+        }
+    }
+
+    // ghosts are invisible and can fly around
+    pub fn set_ghost(player: &mut Player<Self>, ghost: bool) {
+        if player.is_dead == ghost {
+            return;
+        }
+        player.is_dead = ghost;
+        if ghost {
+            player.metadata.flags.insert(EntityFlags::Invisible);
+        } else {
+            player.metadata.flags.remove(EntityFlags::Invisible);
+        }
+        player.dirty_metadata = true;
+
+        player.write_packet(&PlayerAbilities {
+            invulnerable: ghost,
+            flying: ghost,
+            allow_flying: ghost,
+            creative_mode: false,
+            fly_speed: if ghost { 0.1 } else { 0.0 },
+            walk_speed: config().walk_speed(),
+        });
+    }
+
     pub fn get_current_room(&self) -> Option<Rc<RefCell<Room>>> {
         if let Some((room, _)) = &self.current_room {
             return Some(room.clone())
@@ -205,6 +328,35 @@ impl DungeonPlayer {
         None
     }
 
+    fn update_tab_list(player: &mut Player<DungeonPlayer>) {
+        let world = player.world();
+        let score = world.score();
+
+        player.write_packet(&PlayerListHeaderFooter {
+            header: ChatComponent::new(format!(
+                "\n§c§lThe Catacombs §7({})\n",
+                world.floor.name(),
+            )),
+            footer: ChatComponent::new(formatdoc! {r#"
+
+                    §6§lScore: §r§e{total} §7({rank}§7)
+                    §7Skill: §a{skill} §7Exploration: §a{exploration}
+                    §7Speed: §a{speed} §7Bonus: §a{bonus}
+                    §7Secrets Found: §b{secrets_found}/{secrets_total} §7Crypts: §6{crypts}
+                "#,
+                total = score.total(),
+                rank = score.rank().display(),
+                skill = score.skill,
+                exploration = score.exploration,
+                speed = score.speed,
+                bonus = score.bonus,
+                secrets_found = world.secrets_found,
+                secrets_total = world.secrets_total,
+                crypts = world.crypts_blown,
+            }),
+        });
+    }
+
     fn update_sidebar(player: &mut Player<DungeonPlayer>) {
         // really scuffed icl
 
@@ -220,6 +372,7 @@ impl DungeonPlayer {
         };
 
         let (sb_month, sb_day, day_suffix) = get_sb_date();
+        let floor = player.world().floor.name();
         let sidebar = &mut player.extension.sidebar;
 
         sidebar.push(&formatdoc! {r#"
@@ -228,7 +381,7 @@ impl DungeonPlayer {
 
                 {sb_month} {sb_day}{day_suffix}
                 §7{time}
-                 §7⏣ §cThe Catacombs §7(F7)
+                 §7⏣ §cThe Catacombs §7({floor})
 
             "#,
 
@@ -250,25 +403,10 @@ impl DungeonPlayer {
                     sidebar.new_line();
                 }
             }
-            DungeonState::Started { ticks } => {
+            DungeonState::Started { .. } | DungeonState::Finished { .. } | DungeonState::Failed { .. } => {
                 let sidebar = &mut player.extension.sidebar;
 
-                // this is scuffed but it works
-                let seconds = ticks / 20;
-                let time = if seconds >= 60 {
-                    let minutes = seconds / 60;
-                    let seconds = seconds % 60;
-                    format!(
-                        "{}{}m{}{}s",
-                        if minutes < 10 { "0" } else { "" },
-                        minutes,
-                        if seconds < 10 { "0" } else { "" },
-                        seconds
-                    )
-                } else {
-                    let seconds = seconds % 60;
-                    format!("{}{}s", if seconds < 10 { "0" } else { "" }, seconds)
-                };
+                let time = format_time(world.run_ticks());
                 let (has_blood_key, wither_key_count) = (
                     if world.blood_key_count != 0 { "§a✓" } else { "§c✖" },
                     world.wither_key_count,
@@ -282,7 +420,7 @@ impl DungeonPlayer {
                         Cleared: §c{clear_percent}% §r§8({score})
 
                     "#,
-                    score = "0",
+                    score = world.score().total(),
                 });
 
                 if world.players.len() == 1 {
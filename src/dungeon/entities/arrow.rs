@@ -0,0 +1,105 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use bevy_ecs::prelude::Component;
+use glam::dvec3;
+use server::block::block_collision::check_block_collisions;
+use server::constants::{ObjectVariant, Sound};
+use server::entity::components::{EntityAppearance, EntityBehaviour};
+use server::entity::entity::MinecraftEntity;
+use server::network::binary::var_int::VarInt;
+use server::network::packets::packet_buffer::PacketBuffer;
+use server::network::protocol::play::clientbound::{DestroyEntites, EntityStatus, EntityTeleport, EntityVelocity, SpawnObject};
+use server::types::aabb::AABB;
+use server::Player;
+
+const GRAVITY: f64 = 0.05;
+const DRAG: f64 = 0.99;
+const KNOCKBACK_STRENGTH: f64 = 0.4;
+
+/// flies until it hits a block or a player, players get hurt and knocked back
+#[derive(Component)]
+pub struct ArrowBehaviour;
+
+impl EntityBehaviour<Dungeon> for ArrowBehaviour {
+    fn tick(entity: &mut MinecraftEntity<Dungeon>, _: &mut Self) {
+        entity.velocity.y -= GRAVITY;
+        entity.velocity *= DRAG;
+        entity.position += entity.velocity;
+
+        let world = entity.world_mut();
+        let aabb = AABB::from_width_height(0.5, 0.5).offset(entity.position);
+
+        if let Some(player) = world.players_mut().find(|player| !player.is_dead && player.collision_aabb().intersects(&aabb)) {
+            let direction = dvec3(entity.velocity.x, 0.0, entity.velocity.z).normalize_or_zero();
+            // 2 is the hurt animation
+            player.write_packet(&EntityStatus {
+                entity_id: VarInt(player.entity_id),
+                logic_op_code: 2,
+            });
+            player.write_packet(&EntityVelocity {
+                entity_id: player.entity_id,
+                velocity_x: direction.x * KNOCKBACK_STRENGTH,
+                velocity_y: KNOCKBACK_STRENGTH,
+                velocity_z: direction.z * KNOCKBACK_STRENGTH,
+            });
+            player.play_sound(Sound::RandomBowHit, 1.0, 1.0);
+            entity.destroy();
+            return;
+        }
+        if check_block_collisions(world, &aabb) {
+            entity.destroy()
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ArrowAppearance;
+
+impl EntityAppearance<Dungeon> for ArrowAppearance {
+    fn enter_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&SpawnObject {
+            entity_id: entity.id,
+            variant: ObjectVariant::Arrow,
+            x: entity.position.x,
+            y: entity.position.y,
+            z: entity.position.z,
+            pitch: 0.0,
+            yaw: 0.0,
+            // has to be above 0 for the velocity to be sent
+            data: 1,
+            velocity_x: entity.velocity.x,
+            velocity_y: entity.velocity.y,
+            velocity_z: entity.velocity.z,
+        });
+    }
+
+    fn leave_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&DestroyEntites {
+            entities: vec![VarInt(entity.id)],
+        });
+    }
+
+    fn update_position(&self, entity: &MinecraftEntity<Dungeon>, packet_buffer: &mut PacketBuffer) {
+        if entity.ticks_existed.is_multiple_of(10) {
+            packet_buffer.write_packet(&EntityTeleport {
+                entity_id: entity.id,
+                pos_x: entity.position.x,
+                pos_y: entity.position.y,
+                pos_z: entity.position.z,
+                yaw: 0.0,
+                pitch: 0.0,
+                on_ground: false,
+            });
+            packet_buffer.write_packet(&EntityVelocity {
+                entity_id: entity.id,
+                velocity_x: entity.velocity.x,
+                velocity_y: entity.velocity.y,
+                velocity_z: entity.velocity.z,
+            })
+        }
+    }
+
+    fn destroy(&self, entity: &MinecraftEntity<Dungeon>, packet: &mut DestroyEntites) {
+        packet.entities.push(VarInt(entity.id))
+    }
+}
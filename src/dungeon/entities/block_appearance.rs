@@ -97,7 +97,7 @@ impl EntityAppearance<Dungeon> for BlockAppearance {
     }
 }
 
-// doesn't prevent clientside gravity whatsoever, used for falling blocks and in ice fill
+// doesn't prevent clientside gravity whatsoever, used for falling blocks, debris and in ice fill
 #[derive(Component)]
 pub struct FallingBlockAppearance {
     pub block: Block
@@ -124,9 +124,9 @@ impl EntityAppearance<Dungeon> for FallingBlockAppearance {
             pitch: 0.0,
             yaw: 0.0,
             data: object_data,
-            velocity_x: 0.0,
-            velocity_y: 0.0,
-            velocity_z: 0.0,
+            velocity_x: entity.velocity.x,
+            velocity_y: entity.velocity.y,
+            velocity_z: entity.velocity.z,
         });
     }
 
@@ -0,0 +1,216 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::entities::hologram::hologram_metadata;
+use bevy_ecs::prelude::Component;
+use bevy_ecs::world::EntityWorldMut;
+use serde::Deserialize;
+use server::constants::{EntityVariant, ObjectVariant};
+use server::entity::components::EntityAppearance;
+use server::entity::entity::MinecraftEntity;
+use server::entity::entity_metadata::{BlazeMetadata, EntityMetadata, SkeletonMetadata, ZombieMetadata};
+use server::network::binary::var_int::VarInt;
+use server::network::packets::packet_buffer::PacketBuffer;
+use server::network::protocol::play::clientbound::{DestroyEntites, EntityTeleport, EntityYawRotate, PacketEntityMetadata, SpawnMob, SpawnObject};
+use server::types::aabb::AABB;
+use server::{Player, World};
+
+pub const MOB_HITBOX: AABB = AABB::from_width_height(0.6, 1.95);
+
+// the nametag is a marker armor stand, which shows its name just above itself
+const NAMETAG_HEIGHT: f64 = 1.95;
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MobKind {
+    ZombieSoldier,
+    ZombieCommander,
+    SkeletonSoldier,
+    SkeletonMaster,
+    Withermancer,
+    Blaze,
+    // comes out of blown up crypts
+    CryptUndead,
+}
+
+impl MobKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MobKind::ZombieSoldier => "Zombie Soldier",
+            MobKind::ZombieCommander => "Zombie Commander",
+            MobKind::SkeletonSoldier => "Skeleton Soldier",
+            MobKind::SkeletonMaster => "Skeleton Master",
+            MobKind::Withermancer => "Withermancer",
+            MobKind::Blaze => "Blaze",
+            MobKind::CryptUndead => "Crypt Undead",
+        }
+    }
+
+    pub fn variant(&self) -> EntityVariant {
+        match self {
+            MobKind::ZombieSoldier | MobKind::ZombieCommander | MobKind::CryptUndead => EntityVariant::Zombie,
+            MobKind::SkeletonSoldier | MobKind::SkeletonMaster | MobKind::Withermancer => EntityVariant::Skeleton,
+            MobKind::Blaze => EntityVariant::Blaze,
+        }
+    }
+
+    pub fn max_health(&self) -> f32 {
+        match self {
+            MobKind::ZombieSoldier => 4_000.0,
+            MobKind::ZombieCommander => 9_000.0,
+            MobKind::SkeletonSoldier => 3_000.0,
+            MobKind::SkeletonMaster => 8_000.0,
+            MobKind::Withermancer => 10_000.0,
+            MobKind::Blaze => 5_000.0,
+            MobKind::CryptUndead => 2_000.0,
+        }
+    }
+
+    pub fn metadata(&self) -> EntityMetadata {
+        match self {
+            MobKind::ZombieSoldier | MobKind::ZombieCommander | MobKind::CryptUndead => EntityMetadata::Zombie(ZombieMetadata::default()),
+            MobKind::SkeletonSoldier | MobKind::SkeletonMaster => EntityMetadata::Skeleton(SkeletonMetadata::default()),
+            MobKind::Withermancer => EntityMetadata::Skeleton(SkeletonMetadata { is_wither: true }),
+            MobKind::Blaze => EntityMetadata::Blaze(BlazeMetadata::default()),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct DungeonMob {
+    pub kind: MobKind,
+    // starred mobs are the ones required to clear a room
+    pub starred: bool,
+}
+
+impl DungeonMob {
+    pub fn display_name(&self, health: f32) -> String {
+        let health = format_health(health);
+        if self.starred {
+            format!("§6✯ §c{} §a{health}§c❤", self.kind.name())
+        } else {
+            format!("§c{} §a{health}§c❤", self.kind.name())
+        }
+    }
+
+    /// updates the health shown in the nametag, or removes the nametag if the mob died
+    pub fn update_nametag(entity: &mut EntityWorldMut, health: f32) {
+        let Some(name) = entity.get::<DungeonMob>().map(|mob| mob.display_name(health)) else {
+            return;
+        };
+        if let Some(mut appearance) = entity.get_mut::<DungeonMobAppearance>() {
+            appearance.name = name.clone();
+        }
+        let Some(mut mc_entity) = entity.get_mut::<MinecraftEntity<Dungeon>>() else {
+            return;
+        };
+        let (nametag_id, position) = (mc_entity.id + 1, mc_entity.position);
+        let world = mc_entity.world_mut();
+
+        if health <= 0.0 {
+            world.write_local_packet(position, &DestroyEntites {
+                entities: vec![VarInt(nametag_id)],
+            });
+        } else {
+            world.write_local_packet(position, &PacketEntityMetadata {
+                entity_id: VarInt(nametag_id),
+                metadata: hologram_metadata(name),
+            });
+        }
+    }
+}
+
+// 12.3k, 1.2M, etc
+fn format_health(health: f32) -> String {
+    let health = health.ceil();
+    if health >= 1_000_000.0 {
+        format!("{:.1}M", health / 1_000_000.0)
+    } else if health >= 1_000.0 {
+        format!("{:.1}k", health / 1_000.0)
+    } else {
+        format!("{health}")
+    }
+}
+
+/// a mob with a nametag floating above it,
+/// the nametag is an invisible armor stand, which uses the entity id after the mob
+#[derive(Component)]
+pub struct DungeonMobAppearance {
+    pub variant: EntityVariant,
+    pub metadata: EntityMetadata,
+    pub name: String,
+}
+
+impl EntityAppearance<Dungeon> for DungeonMobAppearance {
+
+    fn init(&self, world: &mut World<Dungeon>) {
+        // allocate one more entity id for the nametag
+        world.entities.next_entity_id();
+    }
+
+    fn enter_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&SpawnMob {
+            entity_id: entity.id,
+            entity_variant: self.variant,
+            x: entity.position.x,
+            y: entity.position.y,
+            z: entity.position.z,
+            yaw: entity.yaw,
+            pitch: entity.pitch,
+            head_yaw: entity.yaw,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+            metadata: self.metadata.clone(),
+        });
+        player.write_packet(&EntityYawRotate {
+            entity_id: entity.id,
+            yaw: entity.yaw,
+        });
+        player.write_packet(&SpawnObject {
+            entity_id: entity.id + 1,
+            variant: ObjectVariant::ArmorStand,
+            x: entity.position.x,
+            y: entity.position.y + NAMETAG_HEIGHT,
+            z: entity.position.z,
+            pitch: 0.0,
+            yaw: 0.0,
+            data: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+        });
+        player.write_packet(&PacketEntityMetadata {
+            entity_id: VarInt(entity.id + 1),
+            metadata: hologram_metadata(self.name.clone()),
+        });
+    }
+
+    fn leave_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&DestroyEntites {
+            entities: vec![VarInt(entity.id), VarInt(entity.id + 1)],
+        })
+    }
+
+    fn update_position(&self, entity: &MinecraftEntity<Dungeon>, packet_buffer: &mut PacketBuffer) {
+        for (entity_id, y_offset) in [(entity.id, 0.0), (entity.id + 1, NAMETAG_HEIGHT)] {
+            packet_buffer.write_packet(&EntityTeleport {
+                entity_id,
+                pos_x: entity.position.x,
+                pos_y: entity.position.y + y_offset,
+                pos_z: entity.position.z,
+                yaw: entity.yaw,
+                pitch: entity.pitch,
+                on_ground: false,
+            });
+        }
+        packet_buffer.write_packet(&EntityYawRotate {
+            entity_id: entity.id,
+            yaw: entity.yaw,
+        });
+    }
+
+    fn destroy(&self, entity: &MinecraftEntity<Dungeon>, packet: &mut DestroyEntites) {
+        packet.entities.push(VarInt(entity.id));
+        packet.entities.push(VarInt(entity.id + 1));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::entities::dungeon_mob::{DungeonMob, MOB_HITBOX};
+use crate::dungeon::entities::hologram::HologramAppearance;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::prelude::Component;
+use glam::{dvec3, DVec3};
+use rand::{rng, Rng};
+use server::block::block_collision::check_block_collisions;
+use server::entity::components::EntityBehaviour;
+use server::entity::entity::MinecraftEntity;
+use server::network::binary::var_int::VarInt;
+use server::network::protocol::play::clientbound::{EntityStatus, EntityVelocity};
+use server::World;
+
+// how long a dead entity sticks around for, so the client can play the death animation
+const DEATH_ANIMATION_TICKS: u32 = 20;
+const DAMAGE_HOLOGRAM_TICKS: u32 = 20;
+
+const KNOCKBACK_STRENGTH: f64 = 0.4;
+const GRAVITY: f64 = 0.08;
+const FRICTION: f64 = 0.6;
+
+/// entities with this can be damaged, and die once it reaches 0
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    death_ticks: u32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            death_ticks: 0,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+impl EntityBehaviour<Dungeon> for Health {
+    fn tick(entity: &mut MinecraftEntity<Dungeon>, health: &mut Self) {
+        if health.is_dead() {
+            health.death_ticks += 1;
+            if health.death_ticks == DEATH_ANIMATION_TICKS {
+                entity.destroy()
+            }
+            return;
+        }
+        if entity.velocity != DVec3::ZERO {
+            apply_knockback(entity)
+        }
+    }
+}
+
+pub enum DamageTarget {
+    Entity(Entity),
+    Area { center: DVec3, radius: f64 },
+}
+
+/// damage is queued and applied at the start of the next dungeon tick,
+/// so it can be dealt from anywhere, including entity behaviours
+pub struct Damage {
+    pub target: DamageTarget,
+    pub amount: f32,
+    // where the damage came from, entities hit get knocked away from it
+    pub knockback_from: Option<DVec3>,
+}
+
+/// applies all queued damage, returns the entities that died from it
+pub fn apply_queued_damage(world: &mut World<Dungeon>) -> Vec<Entity> {
+    let mut deaths = Vec::new();
+
+    for damage in std::mem::take(&mut world.damage_queue) {
+        let targets = match damage.target {
+            DamageTarget::Entity(entity) => vec![entity],
+            DamageTarget::Area { center, radius } => entities_in_radius(world, center, radius),
+        };
+        for entity in targets {
+            if damage_entity(world, entity, damage.amount, damage.knockback_from) {
+                deaths.push(entity)
+            }
+        }
+    }
+    deaths
+}
+
+/// returns all alive entities with health within the radius
+pub fn entities_in_radius(world: &World<Dungeon>, center: DVec3, radius: f64) -> Vec<Entity> {
+    let mut entities = Vec::new();
+
+    let min_chunk_x = (center.x - radius).floor() as i32 >> 4;
+    let min_chunk_z = (center.z - radius).floor() as i32 >> 4;
+    let max_chunk_x = (center.x + radius).floor() as i32 >> 4;
+    let max_chunk_z = (center.z + radius).floor() as i32 >> 4;
+
+    for chunk_x in min_chunk_x..=max_chunk_x {
+        for chunk_z in min_chunk_z..=max_chunk_z {
+            let Some(chunk) = world.chunk_grid.get_chunk(chunk_x, chunk_z) else {
+                continue;
+            };
+            for entity in chunk.entities.iter() {
+                let entity_ref = world.entities.get_entity(*entity);
+                let (Some(health), Some(mc_entity)) = (
+                    entity_ref.get::<Health>(),
+                    entity_ref.get::<MinecraftEntity<Dungeon>>(),
+                ) else {
+                    continue;
+                };
+                if !health.is_dead() && mc_entity.position.distance_squared(center) <= radius * radius {
+                    entities.push(*entity)
+                }
+            }
+        }
+    }
+    entities
+}
+
+// returns true if this killed the entity
+fn damage_entity(world: &mut World<Dungeon>, entity: Entity, amount: f32, knockback_from: Option<DVec3>) -> bool {
+    // could've been removed since the damage was queued
+    if !world.entities.contains(entity) {
+        return false;
+    }
+    let mut entity_mut = world.entities.get_entity_mut(entity);
+
+    let Some(mut health) = entity_mut.get_mut::<Health>() else {
+        return false;
+    };
+    if health.is_dead() {
+        return false;
+    }
+    health.current = (health.current - amount).max(0.0);
+    let (current, is_dead) = (health.current, health.is_dead());
+
+    if entity_mut.contains::<DungeonMob>() {
+        DungeonMob::update_nametag(&mut entity_mut, current);
+    }
+
+    let Some(mut mc_entity) = entity_mut.get_mut::<MinecraftEntity<Dungeon>>() else {
+        return is_dead;
+    };
+    let (entity_id, position) = (mc_entity.id, mc_entity.position);
+
+    if let Some(from) = knockback_from && !is_dead {
+        let direction = dvec3(position.x - from.x, 0.0, position.z - from.z).normalize_or_zero();
+        mc_entity.velocity = dvec3(
+            direction.x * KNOCKBACK_STRENGTH,
+            KNOCKBACK_STRENGTH,
+            direction.z * KNOCKBACK_STRENGTH,
+        );
+        let velocity = mc_entity.velocity;
+        world.write_local_packet(position, &EntityVelocity {
+            entity_id,
+            velocity_x: velocity.x,
+            velocity_y: velocity.y,
+            velocity_z: velocity.z,
+        });
+    }
+
+    // 2 is the hurt animation, 3 is death
+    world.write_local_packet(position, &EntityStatus {
+        entity_id: VarInt(entity_id),
+        logic_op_code: if is_dead { 3 } else { 2 },
+    });
+
+    let mut rng = rng();
+    let offset = dvec3(
+        rng.random_range(-0.5..0.5),
+        rng.random_range(1.0..1.5),
+        rng.random_range(-0.5..0.5),
+    );
+    world.spawn_entity(
+        position + offset,
+        0.0,
+        0.0,
+        HologramAppearance {
+            text: format!("§7{}", format_number(amount)),
+        },
+        Lifetime {
+            ticks: DAMAGE_HOLOGRAM_TICKS,
+        },
+    );
+
+    is_dead
+}
+
+fn apply_knockback(entity: &mut MinecraftEntity<Dungeon>) {
+    let world = entity.world();
+    let mut position = entity.position;
+    let mut velocity = entity.velocity;
+    let mut on_ground = false;
+
+    velocity.y -= GRAVITY;
+
+    // move one axis at a time, so it slides along walls instead of sticking to them
+    for axis in 0..3 {
+        let mut next = position;
+        next[axis] += velocity[axis];
+
+        if check_block_collisions(world, &MOB_HITBOX.offset(next)) {
+            if axis == 1 && velocity.y < 0.0 {
+                on_ground = true;
+            }
+            velocity[axis] = 0.0;
+        } else {
+            position = next;
+        }
+    }
+
+    velocity.x *= FRICTION;
+    velocity.z *= FRICTION;
+    if on_ground && velocity.x.abs() < 0.005 && velocity.z.abs() < 0.005 {
+        velocity = DVec3::ZERO;
+    }
+
+    entity.position = position;
+    entity.velocity = velocity;
+}
+
+/// formats with commas, like 12,345
+pub fn format_number(value: f32) -> String {
+    let digits = (value.round() as u64).to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (index, char) in digits.chars().enumerate() {
+        if index != 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(char);
+    }
+    formatted
+}
@@ -0,0 +1,76 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use bevy_ecs::prelude::Component;
+use server::constants::ObjectVariant;
+use server::entity::components::EntityAppearance;
+use server::entity::entity::MinecraftEntity;
+use server::entity::entity_metadata::{ArmorStandMetadata, EntityFlags, EntityMetadata};
+use server::network::binary::var_int::VarInt;
+use server::network::packets::packet_buffer::PacketBuffer;
+use server::network::protocol::play::clientbound::{DestroyEntites, EntityTeleport, PacketEntityMetadata, SpawnObject};
+use server::Player;
+
+// no base plate + marker, markers don't have a hitbox,
+// so they don't get in the way when attacking whatever is under them
+const ARMOR_STAND_FLAGS: u8 = 0x08 | 0x10;
+
+/// metadata for an invisible armor stand that only shows its name
+pub fn hologram_metadata(text: String) -> EntityMetadata {
+    EntityMetadata::ArmorStand(ArmorStandMetadata {
+        flags: EntityFlags::Invisible.into(),
+        custom_name: text,
+        custom_name_visible: true,
+        armor_stand_flags: ARMOR_STAND_FLAGS,
+    })
+}
+
+/// floating text, like damage numbers
+#[derive(Component)]
+pub struct HologramAppearance {
+    pub text: String,
+}
+
+impl EntityAppearance<Dungeon> for HologramAppearance {
+
+    fn enter_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&SpawnObject {
+            entity_id: entity.id,
+            variant: ObjectVariant::ArmorStand,
+            x: entity.position.x,
+            y: entity.position.y,
+            z: entity.position.z,
+            pitch: 0.0,
+            yaw: 0.0,
+            data: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+        });
+        player.write_packet(&PacketEntityMetadata {
+            entity_id: VarInt(entity.id),
+            metadata: hologram_metadata(self.text.clone()),
+        });
+    }
+
+    fn leave_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&DestroyEntites {
+            entities: vec![VarInt(entity.id)],
+        })
+    }
+
+    fn update_position(&self, entity: &MinecraftEntity<Dungeon>, packet_buffer: &mut PacketBuffer) {
+        packet_buffer.write_packet(&EntityTeleport {
+            entity_id: entity.id,
+            pos_x: entity.position.x,
+            pos_y: entity.position.y,
+            pos_z: entity.position.z,
+            yaw: entity.yaw,
+            pitch: entity.pitch,
+            on_ground: false,
+        });
+    }
+
+    fn destroy(&self, entity: &MinecraftEntity<Dungeon>, packet: &mut DestroyEntites) {
+        packet.entities.push(VarInt(entity.id))
+    }
+}
@@ -0,0 +1,65 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use bevy_ecs::prelude::Component;
+use server::constants::ObjectVariant;
+use server::entity::components::EntityAppearance;
+use server::entity::entity::MinecraftEntity;
+use server::entity::entity_metadata::{EntityMetadata, ItemMetadata};
+use server::inventory::item_stack::ItemStack;
+use server::network::binary::var_int::VarInt;
+use server::network::packets::packet_buffer::PacketBuffer;
+use server::network::protocol::play::clientbound::{DestroyEntites, EntityTeleport, PacketEntityMetadata, SpawnObject};
+use server::Player;
+
+/// a dropped item lying on the ground, it can't be picked up clientside
+#[derive(Component)]
+pub struct ItemAppearance {
+    pub item: ItemStack,
+}
+
+impl EntityAppearance<Dungeon> for ItemAppearance {
+
+    fn enter_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&SpawnObject {
+            entity_id: entity.id,
+            variant: ObjectVariant::Item,
+            x: entity.position.x,
+            y: entity.position.y,
+            z: entity.position.z,
+            pitch: 0.0,
+            yaw: 0.0,
+            data: 1,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            velocity_z: 0.0,
+        });
+        player.write_packet(&PacketEntityMetadata {
+            entity_id: VarInt(entity.id),
+            metadata: EntityMetadata::Item(ItemMetadata {
+                item: Some(self.item.clone()),
+            }),
+        });
+    }
+
+    fn leave_player_view(&self, entity: &MinecraftEntity<Dungeon>, player: &mut Player<DungeonPlayer>) {
+        player.write_packet(&DestroyEntites {
+            entities: vec![VarInt(entity.id)],
+        })
+    }
+
+    fn update_position(&self, entity: &MinecraftEntity<Dungeon>, packet_buffer: &mut PacketBuffer) {
+        packet_buffer.write_packet(&EntityTeleport {
+            entity_id: entity.id,
+            pos_x: entity.position.x,
+            pos_y: entity.position.y,
+            pos_z: entity.position.z,
+            yaw: 0.0,
+            pitch: 0.0,
+            on_ground: true,
+        });
+    }
+
+    fn destroy(&self, entity: &MinecraftEntity<Dungeon>, packet: &mut DestroyEntites) {
+        packet.entities.push(VarInt(entity.id));
+    }
+}
@@ -1,4 +1,9 @@
 pub mod npc;
 pub mod block_appearance;
 pub mod moving_block_behaviour;
-pub mod components;
\ No newline at end of file
+pub mod components;
+pub mod dungeon_mob;
+pub mod health;
+pub mod hologram;
+pub mod arrow;
+pub mod item_appearance;
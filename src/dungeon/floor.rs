@@ -0,0 +1,75 @@
+use anyhow::bail;
+use serde::Deserialize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Floor {
+    Entrance,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    #[default]
+    F7,
+}
+
+impl Floor {
+    pub const ALL: [Floor; 8] = [
+        Floor::Entrance,
+        Floor::F1,
+        Floor::F2,
+        Floor::F3,
+        Floor::F4,
+        Floor::F5,
+        Floor::F6,
+        Floor::F7,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Floor::Entrance => "E",
+            Floor::F1 => "F1",
+            Floor::F2 => "F2",
+            Floor::F3 => "F3",
+            Floor::F4 => "F4",
+            Floor::F5 => "F5",
+            Floor::F6 => "F6",
+            Floor::F7 => "F7",
+        }
+    }
+
+    /// going over this starts lowering the speed score
+    pub fn time_limit_seconds(&self) -> u32 {
+        match self {
+            Floor::F4 | Floor::F6 => 720,
+            Floor::F7 => 840,
+            _ => 600,
+        }
+    }
+
+    /// percent of secrets that need to be found for full exploration score
+    pub fn secrets_required_percent(&self) -> usize {
+        match self {
+            Floor::Entrance | Floor::F1 => 30,
+            Floor::F2 => 40,
+            Floor::F3 => 50,
+            Floor::F4 => 60,
+            Floor::F5 => 70,
+            Floor::F6 => 85,
+            Floor::F7 => 100,
+        }
+    }
+}
+
+impl TryFrom<String> for Floor {
+    type Error = anyhow::Error;
+
+    fn try_from(name: String) -> anyhow::Result<Self> {
+        let Some(floor) = Floor::ALL.into_iter().find(|floor| floor.name().eq_ignore_ascii_case(&name)) else {
+            bail!("unknown floor \"{name}\", expected one of E, F1, F2, F3, F4, F5, F6, F7")
+        };
+        Ok(floor)
+    }
+}
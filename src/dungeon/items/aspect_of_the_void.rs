@@ -21,10 +21,14 @@ impl DungeonItem for AspectOfTheVoid {
         if player.is_sneaking {
             etherwarp(player)
         } else {
-            instant_transmission(player, 12.0)
+            instant_transmission(player, 12.0);
         }
     }
 
+    fn melee_damage(&self) -> f32 {
+        1_500.0
+    }
+
     fn item_stack(&self) -> ItemStack {
         ItemStack {
             item: 277,
@@ -178,10 +182,11 @@ fn traverse_voxels(chunk_grid: &ChunkGrid<Dungeon>, start: DVec3, end: DVec3) ->
 
 
 
+// returns where the player ends up
 pub fn instant_transmission(
     player: &mut Player<DungeonPlayer>,
     distance: f64,
-) {
+) -> DVec3 {
     // if let Some(room_rc) = player.extension.get_current_room() {
     //     match room_rc.borrow().data.room_type {
     //         RoomType::Trap | RoomType::Puzzle => return,
@@ -229,6 +234,8 @@ pub fn instant_transmission(
         current_block = Some(block_pos);
     }
 
+    let mut destination = player.position;
+
     // todo: sounds
     if let Some(position) = current_block {
         let position = dvec3(position.x as f64 + 0.5, position.y as f64, position.z as f64 + 0.5);
@@ -246,11 +253,13 @@ pub fn instant_transmission(
             yaw: 0.0,
             pitch: 0.0,
             flags: Relative::Yaw | Relative::Pitch,
-        })
+        });
+        destination = position;
     }
     if block_in_way {
         // play block in the way sound
     }
+    destination
 }
 
 fn is_valid(chunk_grid: &ChunkGrid<Dungeon>, position: IVec3) -> bool {
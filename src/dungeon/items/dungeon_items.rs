@@ -15,6 +15,9 @@ use server::inventory::item_stack::ItemStack;
 use server::player::packet_processing::BlockInteractResult;
 use server::Player;
 
+// damage dealt when hitting with anything that isn't a weapon, including nothing
+pub const BASE_MELEE_DAMAGE: f32 = 100.0;
+
 #[enum_dispatch]
 pub trait DungeonItem {
 
@@ -22,6 +25,10 @@ pub trait DungeonItem {
     
     fn on_start_dig(&self, _player: &mut Player<DungeonPlayer>, _position: IVec3) {}
 
+    fn melee_damage(&self) -> f32 {
+        BASE_MELEE_DAMAGE
+    }
+
     fn item_stack(&self) -> ItemStack;
 
     fn can_move_in_inv(&self) -> bool {
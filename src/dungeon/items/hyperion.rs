@@ -1,20 +1,39 @@
 use crate::dungeon::dungeon_player::DungeonPlayer;
 use crate::dungeon::items::aspect_of_the_void::instant_transmission;
 use crate::dungeon::items::dungeon_items::DungeonItem;
+use glam::Vec3;
 use indoc::indoc;
+use server::constants::{Particle, Sound};
 use server::inventory::item_stack::ItemStack;
 use server::network::binary::nbt::NBT;
 use server::player::packet_processing::BlockInteractResult;
 use server::Player;
 
+const WITHER_IMPACT_RADIUS: f64 = 6.0;
+const WITHER_IMPACT_DAMAGE: f32 = 10_000.0;
+
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 pub struct Hyperion;
 
 impl DungeonItem for Hyperion {
 
     fn on_interact(&self, player: &mut Player<DungeonPlayer>, _: Option<BlockInteractResult>) {
-        // todo: the small cd it has, wither impact, sounds
-        instant_transmission(player, 10.0)
+        // todo: the small cd it has, wither shield
+        let position = instant_transmission(player, 10.0);
+
+        let world = player.world_mut();
+        world.damage_area(position, WITHER_IMPACT_RADIUS, WITHER_IMPACT_DAMAGE);
+        world.spawn_particle(
+            Particle::HugeExplosion,
+            position.as_vec3(),
+            Vec3::ZERO,
+            1
+        );
+        world.play_sound_at(Sound::RandomExplode, 1.0, 1.0, position);
+    }
+
+    fn melee_damage(&self) -> f32 {
+        3_000.0
     }
 
     fn item_stack(&self) -> ItemStack {
@@ -12,6 +12,9 @@ use server::player::packet_processing::BlockInteractResult;
 use server::types::aabb::AABB;
 use server::{ClientId, Player};
 
+const BAT_EXPLOSION_RADIUS: f64 = 3.0;
+const BAT_EXPLOSION_DAMAGE: f32 = 2_500.0;
+
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 pub struct SpiritSceptre;
 
@@ -68,7 +71,7 @@ impl EntityBehaviour<Dungeon> for SceptreBatBehaviour {
 
         let aabb = AABB::from_width_height(0.9, 0.5).offset(entity.position);
         if check_block_collisions(world, &aabb) {
-            // query nearby entities with dungeon mob and kill them
+            world.damage_area(entity.position, BAT_EXPLOSION_RADIUS, BAT_EXPLOSION_DAMAGE);
             world.play_sound_at(Sound::RandomExplode, 1.0, 0.9, entity.position);
             entity.destroy()
         }
@@ -9,6 +9,9 @@ use server::network::binary::nbt::NBT;
 use server::player::packet_processing::BlockInteractResult;
 use server::Player;
 
+const EXPLOSION_RADIUS: f64 = 4.0;
+const EXPLOSION_DAMAGE: f32 = 1_000.0;
+
 #[derive(Copy, Clone, Hash, Eq, PartialEq)]
 pub struct SuperboomTNT;
 
@@ -21,15 +24,9 @@ impl DungeonItem for SuperboomTNT {
             }
 
             player.sync_inventory();
-            player.play_sound_at(
-                Sound::RandomExplode,
-                1.0,
-                0.8,
-                block.position.as_dvec3()
-            );
+            explode(player, block.position);
 
             player.add_item_cooldown(&SuperboomTNT.into(), Cooldown::from_ticks(10, true))
-            // get current room, iterate over crypts and walls and explode
         }
     }
 
@@ -38,12 +35,7 @@ impl DungeonItem for SuperboomTNT {
             return;
         }
 
-        player.play_sound_at(
-            Sound::RandomExplode,
-            1.0,
-            0.8,
-            position.as_dvec3()
-        );
+        explode(player, position);
 
         player.add_item_cooldown(&SuperboomTNT.into(), Cooldown::from_ticks(7, true))
     }
@@ -67,4 +59,14 @@ impl DungeonItem for SuperboomTNT {
             ])),
         }
     }
-}
\ No newline at end of file
+}
+
+fn explode(player: &mut Player<DungeonPlayer>, position: IVec3) {
+    let center = position.as_dvec3() + 0.5;
+    player.play_sound_at(Sound::RandomExplode, 1.0, 0.8, center);
+    player.world_mut().damage_area(center, EXPLOSION_RADIUS, EXPLOSION_DAMAGE);
+
+    if let Some(room_rc) = player.get_current_room() && player.world().has_started() {
+        room_rc.borrow_mut().explode(player.world_mut(), center, EXPLOSION_RADIUS);
+    }
+}
@@ -0,0 +1,399 @@
+use crate::dungeon::seeded_rng::{seeded_rng, SeededRng};
+use anyhow::bail;
+use rand::prelude::{IndexedRandom, SliceRandom};
+use rand::Rng;
+use std::collections::VecDeque;
+
+const GRID_SIZE: usize = 6;
+const CELL_COUNT: usize = GRID_SIZE * GRID_SIZE;
+const DOOR_COUNT: usize = 60;
+
+// ids used by Dungeon::from_string, normal rooms use any id after these
+const EMPTY: u8 = 0;
+const ENTRANCE: u8 = 1;
+const FAIRY: u8 = 2;
+const BLOOD: u8 = 3;
+const PUZZLE: u8 = 4;
+const TRAP: u8 = 5;
+const YELLOW: u8 = 6;
+const FIRST_NORMAL_ID: u8 = 7;
+
+const NO_DOOR: char = '9';
+const NORMAL_DOOR: char = '0';
+const WITHER_DOOR: char = '1';
+const BLOOD_DOOR: char = '2';
+const ENTRANCE_DOOR: char = '3';
+
+// how far blood has to be from entrance, so the run isn't over instantly
+const MIN_BLOOD_DISTANCE: usize = 5;
+const MAX_WITHER_DOORS: usize = 4;
+
+// placing special rooms almost never fails, if it keeps failing the constants above can't work
+const MAX_ATTEMPTS: usize = 1000;
+
+#[derive(Copy, Clone)]
+enum Shape {
+    OneByOne,
+    OneByTwo,
+    OneByThree,
+    OneByFour,
+    TwoByTwo,
+    L,
+}
+
+impl Shape {
+    const WEIGHTED: [(Shape, u32); 6] = [
+        (Shape::OneByOne, 30),
+        (Shape::OneByTwo, 30),
+        (Shape::OneByThree, 12),
+        (Shape::OneByFour, 6),
+        (Shape::TwoByTwo, 8),
+        (Shape::L, 14),
+    ];
+
+    // every orientation, as x and z offsets from the cell it's being placed at,
+    // which always has to be part of the shape
+    fn orientations(&self) -> &'static [&'static [(isize, isize)]] {
+        match self {
+            Shape::OneByOne => &[&[(0, 0)]],
+            Shape::OneByTwo => &[&[(0, 0), (1, 0)], &[(0, 0), (0, 1)]],
+            Shape::OneByThree => &[&[(0, 0), (1, 0), (2, 0)], &[(0, 0), (0, 1), (0, 2)]],
+            Shape::OneByFour => &[&[(0, 0), (1, 0), (2, 0), (3, 0)], &[(0, 0), (0, 1), (0, 2), (0, 3)]],
+            Shape::TwoByTwo => &[&[(0, 0), (1, 0), (0, 1), (1, 1)]],
+            Shape::L => &[
+                &[(0, 0), (1, 0), (0, 1)],
+                &[(0, 0), (1, 0), (1, 1)],
+                &[(0, 0), (0, 1), (1, 1)],
+                &[(0, 0), (0, 1), (-1, 1)],
+            ],
+        }
+    }
+}
+
+/// generates a layout for a full 6x6 dungeon using the seeded rng,
+/// in the same format as the layouts file, so it can be used with Dungeon::from_string.
+pub fn generate_layout() -> anyhow::Result<String> {
+    let mut rng = seeded_rng();
+
+    let Some(cells) = (0..MAX_ATTEMPTS).find_map(|_| place_special_rooms(&mut rng)) else {
+        bail!("couldn't place the special rooms after {MAX_ATTEMPTS} attempts")
+    };
+    let (cells, rooms) = place_normal_rooms(&mut rng, cells);
+    let doors = place_doors(&mut rng, &cells, &rooms);
+
+    let mut layout = String::with_capacity(CELL_COUNT * 2 + DOOR_COUNT);
+    for id in cells {
+        layout.push_str(&format!("{id:02}"));
+    }
+    layout.extend(doors);
+    Ok(layout)
+}
+
+// returns None if the rooms ended up somewhere they can't all be connected
+fn place_special_rooms(rng: &mut SeededRng) -> Option<[u8; CELL_COUNT]> {
+    let mut cells = [EMPTY; CELL_COUNT];
+
+    let edges = (0..CELL_COUNT).filter(|cell| is_edge(*cell)).collect::<Vec<usize>>();
+    let entrance = *edges.choose(rng)?;
+    let blood = *edges.iter()
+        .copied()
+        .filter(|cell| distance(entrance, *cell) >= MIN_BLOOD_DISTANCE)
+        .collect::<Vec<usize>>()
+        .choose(rng)?;
+    cells[entrance] = ENTRANCE;
+    cells[blood] = BLOOD;
+
+    let puzzles = rng.random_range(3..=5);
+    let mut special_rooms = vec![FAIRY, TRAP, YELLOW];
+    special_rooms.extend([PUZZLE].repeat(puzzles));
+
+    for id in special_rooms {
+        let empty = (0..CELL_COUNT).filter(|cell| cells[*cell] == EMPTY).collect::<Vec<usize>>();
+        cells[*empty.choose(rng)?] = id;
+    }
+
+    // every dead end needs something to connect to,
+    // and everything that isn't a dead end has to be connected together
+    let hubs = (0..CELL_COUNT).filter(|cell| is_hub(cells[*cell])).collect::<Vec<usize>>();
+    let all_dead_ends_connect = (0..CELL_COUNT)
+        .filter(|cell| cells[*cell] != EMPTY && !is_hub(cells[*cell]))
+        .all(|cell| neighbours(cell).any(|other| is_hub(cells[other])));
+
+    if !all_dead_ends_connect || flood_fill(hubs[0], |cell| is_hub(cells[cell])).len() != hubs.len() {
+        return None;
+    }
+    Some(cells)
+}
+
+// fills every empty cell with normal rooms, returns the cells for each room
+fn place_normal_rooms(rng: &mut SeededRng, mut cells: [u8; CELL_COUNT]) -> ([u8; CELL_COUNT], Vec<Vec<usize>>) {
+    let mut rooms = (0..CELL_COUNT)
+        .filter(|cell| cells[*cell] != EMPTY)
+        .map(|cell| vec![cell])
+        .collect::<Vec<Vec<usize>>>();
+
+    let mut order = (0..CELL_COUNT).collect::<Vec<usize>>();
+    order.shuffle(rng);
+
+    let mut next_id = FIRST_NORMAL_ID;
+    for cell in order {
+        if cells[cell] != EMPTY {
+            continue;
+        }
+        let shape = Shape::WEIGHTED.choose_weighted(rng, |(_, weight)| *weight).unwrap().0;
+
+        let mut orientations = shape.orientations().to_vec();
+        orientations.shuffle(rng);
+
+        let segments = orientations.into_iter()
+            .find_map(|offsets| fit_shape(&cells, cell, offsets))
+            .unwrap_or_else(|| vec![cell]);
+
+        for segment in segments.iter() {
+            cells[*segment] = next_id;
+        }
+        rooms.push(segments);
+        next_id += 1;
+    }
+    (cells, rooms)
+}
+
+// connects every room with a spanning tree of doors,
+// the doors between entrance and blood become wither doors
+fn place_doors(rng: &mut SeededRng, cells: &[u8; CELL_COUNT], rooms: &[Vec<usize>]) -> [char; DOOR_COUNT] {
+    let mut room_of = [0; CELL_COUNT];
+    for (index, room) in rooms.iter().enumerate() {
+        for cell in room.iter() {
+            room_of[*cell] = index;
+        }
+    }
+    let mut connections: Vec<Vec<(usize, usize)>> = vec![Vec::new(); rooms.len()];
+    let add_door = |connections: &mut Vec<Vec<(usize, usize)>>, a: usize, b: usize| {
+        connections[room_of[a]].push((room_of[b], door_index(a, b)));
+        connections[room_of[b]].push((room_of[a], door_index(a, b)));
+    };
+
+    // random spanning tree of everything that can have more than 1 door
+    let hub_rooms = (0..rooms.len()).filter(|room| is_hub(cells[rooms[*room][0]])).collect::<Vec<usize>>();
+    let mut in_tree = vec![false; rooms.len()];
+    let mut frontier: Vec<(usize, usize)> = Vec::new();
+
+    let first = *hub_rooms.choose(rng).unwrap();
+    in_tree[first] = true;
+    add_frontier(&mut frontier, cells, &rooms[first]);
+
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.random_range(0..frontier.len()));
+        if in_tree[room_of[to]] {
+            continue;
+        }
+        in_tree[room_of[to]] = true;
+        add_door(&mut connections, from, to);
+        add_frontier(&mut frontier, cells, &rooms[room_of[to]]);
+    }
+
+    // dead ends only get a single door
+    for room in rooms.iter().filter(|room| !is_hub(cells[room[0]])) {
+        let cell = room[0];
+        let options = neighbours(cell).filter(|other| is_hub(cells[*other])).collect::<Vec<usize>>();
+        add_door(&mut connections, cell, *options.choose(rng).unwrap());
+    }
+
+    let mut doors = [NO_DOOR; DOOR_COUNT];
+    for room in connections.iter() {
+        for (_, door) in room.iter() {
+            doors[*door] = NORMAL_DOOR;
+        }
+    }
+
+    let entrance = room_of[cells.iter().position(|id| *id == ENTRANCE).unwrap()];
+    let blood = room_of[cells.iter().position(|id| *id == BLOOD).unwrap()];
+
+    let mut path = critical_path(&connections, entrance, blood);
+    let entrance_door = path.remove(0);
+    let blood_door = path.pop().unwrap();
+
+    path.shuffle(rng);
+    for door in path.into_iter().take(MAX_WITHER_DOORS) {
+        doors[door] = WITHER_DOOR;
+    }
+    doors[entrance_door] = ENTRANCE_DOOR;
+    doors[blood_door] = BLOOD_DOOR;
+    doors
+}
+
+// adds every door that could be placed from this room to another hub room
+fn add_frontier(frontier: &mut Vec<(usize, usize)>, cells: &[u8; CELL_COUNT], room: &[usize]) {
+    for cell in room.iter() {
+        for other in neighbours(*cell) {
+            if cells[other] != cells[*cell] && is_hub(cells[other]) {
+                frontier.push((*cell, other))
+            }
+        }
+    }
+}
+
+// doors on the way from one room to the other, in order
+fn critical_path(connections: &[Vec<(usize, usize)>], from: usize, to: usize) -> Vec<usize> {
+    let mut previous: Vec<Option<(usize, usize)>> = vec![None; connections.len()];
+    let mut queue = VecDeque::from([from]);
+
+    while let Some(room) = queue.pop_front() {
+        for (other, door) in connections[room].iter() {
+            if *other != from && previous[*other].is_none() {
+                previous[*other] = Some((room, *door));
+                queue.push_back(*other);
+            }
+        }
+    }
+
+    let mut path = Vec::new();
+    let mut current = to;
+    while let Some((room, door)) = previous[current] {
+        path.push(door);
+        current = room;
+    }
+    path.reverse();
+    path
+}
+
+fn fit_shape(cells: &[u8; CELL_COUNT], cell: usize, offsets: &[(isize, isize)]) -> Option<Vec<usize>> {
+    let (x, z) = (cell % GRID_SIZE, cell / GRID_SIZE);
+    offsets.iter()
+        .map(|(offset_x, offset_z)| {
+            let x = x.checked_add_signed(*offset_x).filter(|x| *x < GRID_SIZE)?;
+            let z = z.checked_add_signed(*offset_z).filter(|z| *z < GRID_SIZE)?;
+            let cell = x + z * GRID_SIZE;
+            (cells[cell] == EMPTY).then_some(cell)
+        })
+        .collect()
+}
+
+fn flood_fill(start: usize, include: impl Fn(usize) -> bool) -> Vec<usize> {
+    let mut visited = vec![start];
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(cell) = queue.pop_front() {
+        for other in neighbours(cell) {
+            if include(other) && !visited.contains(&other) {
+                visited.push(other);
+                queue.push_back(other);
+            }
+        }
+    }
+    visited
+}
+
+// rooms that can have more than 1 door
+fn is_hub(id: u8) -> bool {
+    id == EMPTY || id == FAIRY || id >= FIRST_NORMAL_ID
+}
+
+fn is_edge(cell: usize) -> bool {
+    let (x, z) = (cell % GRID_SIZE, cell / GRID_SIZE);
+    x == 0 || z == 0 || x == GRID_SIZE - 1 || z == GRID_SIZE - 1
+}
+
+fn distance(a: usize, b: usize) -> usize {
+    (a % GRID_SIZE).abs_diff(b % GRID_SIZE) + (a / GRID_SIZE).abs_diff(b / GRID_SIZE)
+}
+
+fn neighbours(cell: usize) -> impl Iterator<Item = usize> {
+    let (x, z) = (cell % GRID_SIZE, cell / GRID_SIZE);
+    [
+        (z > 0).then(|| cell - GRID_SIZE),
+        (x < GRID_SIZE - 1).then(|| cell + 1),
+        (z < GRID_SIZE - 1).then(|| cell + GRID_SIZE),
+        (x > 0).then(|| cell - 1),
+    ].into_iter().flatten()
+}
+
+// doors are stored row by row, each row has the 5 doors between cells next to each other,
+// followed by the 6 doors to the row below it
+fn door_index(a: usize, b: usize) -> usize {
+    let (a, b) = (a.min(b), a.max(b));
+    let (x, z) = (a % GRID_SIZE, a / GRID_SIZE);
+    if b == a + 1 {
+        z * 11 + x
+    } else {
+        z * 11 + 5 + x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::door::door_positions::DOOR_POSITIONS;
+    use crate::dungeon::dungeon::{Dungeon, DUNGEON_ORIGIN};
+    use crate::dungeon::room::room_data::RoomType;
+    use server::utils::hasher::deterministic_hasher::DeterministicHashMap;
+    use std::rc::Rc;
+
+    const SEEDS: u64 = 100;
+
+    fn generate(seed: u64) -> String {
+        SeededRng::set_seed(seed);
+        generate_layout().unwrap()
+    }
+
+    fn parse(seed: u64) -> Dungeon {
+        let layout = generate(seed);
+        // no room data, every room uses dummy data
+        Dungeon::from_string(&layout, &DeterministicHashMap::default())
+            .unwrap_or_else(|error| panic!("seed {seed} generated {layout} which failed to parse: {error}"))
+    }
+
+    #[test]
+    fn door_indices_match_door_positions() {
+        for cell in 0..CELL_COUNT {
+            for other in neighbours(cell) {
+                let position = DOOR_POSITIONS[door_index(cell, other)];
+                let (a, b) = (cell.min(other), cell.max(other));
+                let expected_x = DUNGEON_ORIGIN.x + (a % GRID_SIZE) as i32 * 32 + if b == a + 1 { 31 } else { 15 };
+                let expected_z = DUNGEON_ORIGIN.y + (a / GRID_SIZE) as i32 * 32 + if b == a + 1 { 15 } else { 31 };
+                assert_eq!((position.x, position.y), (expected_x, expected_z));
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_layout() {
+        assert_eq!(generate(5), generate(5));
+        assert_ne!(generate(5), generate(6));
+    }
+
+    #[test]
+    fn every_layout_parses() {
+        for seed in 0..SEEDS {
+            let dungeon = parse(seed);
+            let count = |room_type| dungeon.rooms.iter().filter(|room| room.borrow().data.room_type == room_type).count();
+
+            assert_eq!(count(RoomType::Entrance), 1);
+            assert_eq!(count(RoomType::Blood), 1);
+            assert_eq!(count(RoomType::Fairy), 1);
+            assert!((3..=5).contains(&count(RoomType::Puzzle)));
+
+            let segments = dungeon.rooms.iter().map(|room| room.borrow().segments.len()).sum::<usize>();
+            assert_eq!(segments, CELL_COUNT, "seed {seed} didn't fill the grid");
+        }
+    }
+
+    #[test]
+    fn every_room_is_reachable() {
+        for seed in 0..SEEDS {
+            let dungeon = parse(seed);
+            let mut visited = vec![dungeon.entrance_room()];
+            let mut queue = VecDeque::from([dungeon.entrance_room()]);
+
+            while let Some(room) = queue.pop_front() {
+                for neighbour in room.borrow().neighbours() {
+                    if !visited.iter().any(|other| Rc::ptr_eq(other, &neighbour.room)) {
+                        visited.push(neighbour.room.clone());
+                        queue.push_back(neighbour.room.clone());
+                    }
+                }
+            }
+            assert_eq!(visited.len(), dungeon.rooms.len(), "seed {seed} has unreachable rooms");
+        }
+    }
+}
@@ -28,6 +28,13 @@ const CHECKMARK_POSITIONS: [(usize, usize); 30] = [
     (4, 5), (0, 6), (1, 6), (2, 6), (3, 6), (1, 7), (2, 7), (3, 7), (1, 8), (2, 8),
 ];
 
+const CROSS_POSITIONS: [(usize, usize); 32] = [
+    (0, 0), (1, 0), (7, 0), (8, 0), (1, 1), (2, 1), (6, 1), (7, 1), (2, 2), (3, 2),
+    (5, 2), (6, 2), (3, 3), (4, 3), (5, 3), (3, 4), (4, 4), (5, 4), (2, 5), (3, 5),
+    (5, 5), (6, 5), (1, 6), (2, 6), (6, 6), (7, 6), (0, 7), (1, 7), (7, 7), (8, 7),
+    (0, 8), (8, 8),
+];
+
 struct DirtyMapRegion {
     min_x: usize,
     min_y: usize,
@@ -214,18 +221,21 @@ impl DungeonMap {
     
     pub fn draw_checkmark(&mut self, room: &Room) {
         if matches!(room.status, RoomStatus::Complete | RoomStatus::Failed) {
-            let color = match room.status {
+            let (color, positions) = match room.status {
                 RoomStatus::Undiscovered => unreachable!(),
                 RoomStatus::Discovered => unreachable!(),
-                RoomStatus::Complete => GREEN,
-                RoomStatus::Failed => RED,
+                // mob rooms stay white until all their secrets are found
+                RoomStatus::Complete => match room.data.room_type {
+                    Normal | Rare if room.secrets_found() < room.secrets.len() => (WHITE, CHECKMARK_POSITIONS.as_slice()),
+                    _ => (GREEN, CHECKMARK_POSITIONS.as_slice()),
+                },
+                RoomStatus::Failed => (RED, CROSS_POSITIONS.as_slice()),
             };
 
-            // todo render X when failed 
             let x = room.segments[0].x * 20 + 4;
             let y = room.segments[0].z * 20 + 4;
 
-            for (cx, cy) in CHECKMARK_POSITIONS {
+            for &(cx, cy) in positions {
                 self.set_px(x + cx, y + cy, color)
             }
         }
@@ -7,4 +7,7 @@ mod map;
 pub mod menus;
 pub mod entities;
 pub mod seeded_rng;
+pub mod floor;
+pub mod score;
+pub mod layout_generator;
 // mod items;
\ No newline at end of file
@@ -0,0 +1,220 @@
+use crate::config::config;
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::dungeon_mob::{DungeonMob, DungeonMobAppearance, MobKind};
+use crate::dungeon::entities::health::Health;
+use crate::dungeon::entities::npc::NPCBehaviour;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, IVec3};
+use rand::seq::index::sample;
+use server::block::Block;
+use server::constants::{EntityVariant, Sound};
+use server::entity::entity_metadata::{EntityMetadata, SkeletonMetadata};
+use server::network::protocol::play::clientbound::Chat;
+use server::World;
+use std::f64::consts::TAU;
+
+const CENTER: IVec3 = ivec3(15, 69, 15);
+
+// mobs come out of some of the spots on this ring
+const SPAWN_RING_RADIUS: f64 = 8.0;
+const SPAWN_RING_SPOTS: usize = 16;
+const SPAWN_POINTS: usize = 6;
+
+const BLOOD_MOBS: [MobKind; 3] = [
+    MobKind::ZombieCommander,
+    MobKind::SkeletonMaster,
+    MobKind::Withermancer,
+];
+
+// ticks after the blood door opens
+const DIALOGUE: [(usize, &str); 4] = [
+    (0, "Things feel a little more roomy now, eh?"),
+    (40, "I've knocked down those pillars to go for a more... open concept."),
+    (80, "Plus I needed to give my new friends some space to roam..."),
+    (120, "Let's see how you can handle this."),
+];
+const FIRST_WAVE: usize = 140;
+const WAVE_INTERVAL: usize = 100;
+const WAVE_SIZE: usize = 4;
+
+/// how many mobs should have spawned by this tick
+fn spawned_by(ticks: usize, total: usize) -> usize {
+    if ticks < FIRST_WAVE {
+        return 0;
+    }
+    let waves = (ticks - FIRST_WAVE) / WAVE_INTERVAL + 1;
+    (waves * WAVE_SIZE).min(total)
+}
+
+pub struct BloodRoom {
+    spawn_points: Vec<IVec3>,
+    // ticks since the blood door was opened
+    ticks: usize,
+    watcher: Option<Entity>,
+    alive: Vec<Entity>,
+    spawned: usize,
+    portal_open: bool,
+}
+
+impl Default for BloodRoom {
+    fn default() -> Self {
+        let spawn_points = sample(&mut seeded_rng(), SPAWN_RING_SPOTS, SPAWN_POINTS)
+            .into_iter()
+            .map(|spot| {
+                let angle = spot as f64 / SPAWN_RING_SPOTS as f64 * TAU;
+                let offset = dvec3(angle.cos(), 0.0, angle.sin()) * SPAWN_RING_RADIUS;
+                CENTER + offset.round().as_ivec3()
+            })
+            .collect();
+        Self {
+            spawn_points,
+            ticks: 0,
+            watcher: None,
+            alive: Vec::new(),
+            spawned: 0,
+            portal_open: false,
+        }
+    }
+}
+
+fn watcher_says(world: &mut World<Dungeon>, line: &str) {
+    world.write_global_packet(&Chat::new(&format!("§c[BOSS] The Watcher§r§f: {line}")));
+}
+
+impl BloodRoom {
+    fn spawn_mob(&mut self, room: &Room, world: &mut World<Dungeon>) {
+        let spawn = self.spawn_points[self.spawned % self.spawn_points.len()];
+        let kind = BLOOD_MOBS[self.spawned % BLOOD_MOBS.len()];
+        let position = room.get_world_block_position(spawn).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+        let mob = DungeonMob {
+            kind,
+            starred: false,
+        };
+        let entity = world.spawn_entity(
+            position,
+            0.0,
+            0.0,
+            DungeonMobAppearance {
+                variant: kind.variant(),
+                metadata: kind.metadata(),
+                name: mob.display_name(kind.max_health()),
+            },
+            (mob, Health::new(kind.max_health())),
+        );
+        self.alive.push(entity);
+        self.spawned += 1;
+    }
+
+    fn open_portal(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        watcher_says(world, "You have proven yourself. You may pass.");
+        if let Some(watcher) = self.watcher.take() {
+            world.remove_entity(watcher);
+        }
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let IVec3 { x, y, z } = room.get_world_block_position(CENTER + ivec3(x, -1, z));
+                world.chunk_grid.set_block_at(Block::EndPortal, x, y, z);
+            }
+        }
+        self.portal_open = true;
+        room.status = RoomStatus::Complete;
+        world.map.draw_checkmark(room);
+    }
+}
+
+impl RoomImplementation for BloodRoom {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        world.blood_opened_at = Some(world.run_ticks());
+
+        let position = room.get_world_block_position(CENTER + IVec3::Y * 3).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+        self.watcher = Some(world.spawn_entity(
+            position,
+            0.0,
+            0.0,
+            DungeonMobAppearance {
+                variant: EntityVariant::Skeleton,
+                metadata: EntityMetadata::Skeleton(SkeletonMetadata { is_wither: true }),
+                name: "§c§lThe Watcher".to_string(),
+            },
+            NPCBehaviour {
+                default_yaw: 0.0,
+                default_pitch: 0.0,
+            },
+        ));
+    }
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        if self.portal_open {
+            let portal = room.get_world_block_position(CENTER).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+            let entered = room.players().any(|player| {
+                !player.is_dead && player.position.with_y(portal.y).distance(portal) <= 1.5 && (player.position.y - portal.y).abs() < 1.0
+            });
+            if entered {
+                world.play_sound_at(Sound::EndermanPortal, 1.0, 1.0, portal);
+                Dungeon::end_run(world, true);
+            }
+            return;
+        }
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+
+        for (tick, line) in DIALOGUE {
+            if self.ticks == tick {
+                watcher_says(world, line);
+            }
+        }
+
+        let total = config().blood_mobs as usize;
+        let should_spawn = spawned_by(self.ticks, total);
+        if self.spawned < should_spawn {
+            while self.spawned < should_spawn {
+                self.spawn_mob(room, world);
+            }
+            if self.spawned == total {
+                watcher_says(world, "That will be enough for now.");
+            }
+        }
+        self.ticks += 1;
+    }
+
+    fn on_entity_death(&mut self, room: &mut Room, world: &mut World<Dungeon>, entity: Entity) {
+        if !matches!(room.status, RoomStatus::Discovered) || !self.alive.contains(&entity) {
+            return;
+        }
+        self.alive.retain(|it| *it != entity);
+
+        if self.alive.is_empty() && self.spawned == config().blood_mobs as usize {
+            self.open_portal(room, world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::SeededRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn waves_stop_at_the_total() {
+        assert_eq!(spawned_by(0, 10), 0);
+        assert_eq!(spawned_by(FIRST_WAVE - 1, 10), 0);
+        assert_eq!(spawned_by(FIRST_WAVE, 10), WAVE_SIZE);
+        assert_eq!(spawned_by(FIRST_WAVE + WAVE_INTERVAL - 1, 10), WAVE_SIZE);
+        assert_eq!(spawned_by(FIRST_WAVE + WAVE_INTERVAL, 10), WAVE_SIZE * 2);
+        assert_eq!(spawned_by(FIRST_WAVE + WAVE_INTERVAL * 10, 10), 10);
+    }
+
+    #[test]
+    fn spawn_points_are_seeded() {
+        SeededRng::set_seed(3);
+        let first = BloodRoom::default().spawn_points;
+        SeededRng::set_seed(3);
+        assert_eq!(first, BloodRoom::default().spawn_points);
+        assert_eq!(first.iter().collect::<HashSet<_>>().len(), SPAWN_POINTS);
+    }
+}
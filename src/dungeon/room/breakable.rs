@@ -0,0 +1,139 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::block_appearance::FallingBlockAppearance;
+use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::entities::dungeon_mob::{DungeonMob, DungeonMobAppearance, MobKind};
+use crate::dungeon::entities::health::Health;
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_data::RegionData;
+use glam::{dvec3, ivec3, DVec3, IVec3, Vec3};
+use rand::{rng, Rng};
+use server::block::Block;
+use server::constants::Particle;
+use server::entity::entity::MinecraftEntity;
+use server::World;
+
+// not every block turns into debris, walls can be big
+const DEBRIS_CHANCE: f64 = 0.3;
+const DEBRIS_TICKS: u32 = 20;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BreakableKind {
+    Crypt,
+    WeakWall,
+}
+
+/// a crypt or weak wall, blown up by superboom
+pub struct Breakable {
+    pub kind: BreakableKind,
+    // corners in the world, already rotated with the room
+    pub min: IVec3,
+    pub max: IVec3,
+    pub broken: bool,
+}
+
+impl Breakable {
+    pub fn new(kind: BreakableKind, region: &RegionData, room: &Room) -> Self {
+        let size = ivec3(region.width, region.height, region.length) - 1;
+        let from = room.get_world_block_position(region.position);
+        let to = room.get_world_block_position(region.position + size);
+        Self {
+            kind,
+            min: from.min(to),
+            max: from.max(to),
+            broken: false,
+        }
+    }
+
+    /// if any part of it is within the radius
+    pub fn in_range(&self, center: DVec3, radius: f64) -> bool {
+        let closest = center.clamp(self.min.as_dvec3(), self.max.as_dvec3() + 1.0);
+        closest.distance_squared(center) <= radius * radius
+    }
+
+    pub fn blow_up(&mut self, world: &mut World<Dungeon>) {
+        self.broken = true;
+
+        let mut rng = rng();
+        for x in self.min.x..=self.max.x {
+            for y in self.min.y..=self.max.y {
+                for z in self.min.z..=self.max.z {
+                    let block = world.chunk_grid.get_block_at(x, y, z);
+                    if block == Block::Air {
+                        continue;
+                    }
+                    world.chunk_grid.set_block_at(Block::Air, x, y, z);
+
+                    if !rng.random_bool(DEBRIS_CHANCE) {
+                        continue;
+                    }
+                    let debris = world.spawn_entity(
+                        ivec3(x, y, z).as_dvec3() + dvec3(0.5, 0.0, 0.5),
+                        0.0,
+                        0.0,
+                        FallingBlockAppearance {
+                            block,
+                        },
+                        Lifetime {
+                            ticks: DEBRIS_TICKS,
+                        },
+                    );
+                    if let Some(mut entity) = world.entities.get_entity_mut(debris).get_mut::<MinecraftEntity<Dungeon>>() {
+                        entity.velocity = dvec3(
+                            rng.random_range(-0.2..0.2),
+                            rng.random_range(0.2..0.5),
+                            rng.random_range(-0.2..0.2),
+                        );
+                    }
+                }
+            }
+        }
+
+        let center = (self.min.as_dvec3() + self.max.as_dvec3() + 1.0) / 2.0;
+        world.spawn_particle(Particle::HugeExplosion, center.as_vec3(), Vec3::ZERO, 1);
+
+        if self.kind == BreakableKind::Crypt {
+            world.crypts_blown += 1;
+
+            let kind = MobKind::CryptUndead;
+            let mob = DungeonMob {
+                kind,
+                starred: false,
+            };
+            world.spawn_entity(
+                center.with_y(self.min.y as f64),
+                rng.random_range(-180.0..180.0),
+                0.0,
+                DungeonMobAppearance {
+                    variant: kind.variant(),
+                    metadata: kind.metadata(),
+                    name: mob.display_name(kind.max_health()),
+                },
+                (mob, Health::new(kind.max_health())),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::room::room_data::RoomData;
+
+    #[test]
+    fn rotated_regions_cover_the_same_blocks() {
+        let region = RegionData {
+            position: ivec3(3, 68, 5),
+            width: 1,
+            height: 3,
+            length: 4,
+        };
+        let room = Room::test_room(RoomData::dummy());
+        let crypt = Breakable::new(BreakableKind::Crypt, &region, &room);
+
+        // rotated a quarter turn, so its long side is along x now
+        assert_eq!(crypt.max - crypt.min, ivec3(3, 2, 0));
+        assert!(crypt.in_range(crypt.min.as_dvec3() - dvec3(2.0, 0.0, 0.0), 2.0));
+        assert!(!crypt.in_range(crypt.min.as_dvec3() - dvec3(2.5, 0.0, 0.0), 2.0));
+        assert!(crypt.in_range((crypt.max + 1).as_dvec3() + dvec3(0.0, 1.0, 0.0), 1.0));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::entities::block_appearance::BlockAppearance;
+use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::entities::moving_block_behaviour::MovingBlockBehaviour;
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_data::{CrusherData, CrusherDirection};
+use glam::{dvec3, IVec3};
+use server::block::rotatable::Rotate;
+use server::block::Block;
+use server::types::aabb::AABB;
+use server::World;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Motion {
+    // which layer of blocks is moving, and for how many ticks it has been
+    Extending { step: u32, ticks: u32 },
+    Retracting { step: u32, ticks: u32 },
+}
+
+/// sits retracted, moves out, sits extended, then moves back in. none while paused
+fn motion(data: &CrusherData, ticks: u32) -> Option<Motion> {
+    let travel = data.length * data.ticks_per_block;
+    let ticks = ticks % cycle_ticks(data);
+
+    let (moving, extending) = if ticks < data.pause_ticks {
+        return None;
+    } else if ticks < data.pause_ticks + travel {
+        (ticks - data.pause_ticks, true)
+    } else if ticks < 2 * data.pause_ticks + travel {
+        return None;
+    } else {
+        (ticks - 2 * data.pause_ticks - travel, false)
+    };
+    let (step, ticks) = (moving / data.ticks_per_block, moving % data.ticks_per_block);
+    Some(if extending {
+        Motion::Extending { step, ticks }
+    } else {
+        Motion::Retracting { step, ticks }
+    })
+}
+
+/// how long it takes to go out and back again, never 0 so it can be used in a range or with %
+pub fn cycle_ticks(data: &CrusherData) -> u32 {
+    (2 * (data.length * data.ticks_per_block + data.pause_ticks)).max(1)
+}
+
+pub struct Crusher {
+    data: CrusherData,
+    // ticks before it starts moving, so crushers aren't all in sync
+    delay: u32,
+    // world positions of the face and the blocks it's made of, set when the room is discovered
+    face: Vec<(IVec3, Block)>,
+    // one block in the direction it moves, rotated with the room
+    offset: IVec3,
+}
+
+impl Crusher {
+    pub fn new(data: CrusherData, delay: u32) -> Self {
+        // room data rejects 0, but it's divided by when moving
+        let data = CrusherData {
+            ticks_per_block: data.ticks_per_block.max(1),
+            ..data
+        };
+        Self {
+            data,
+            delay,
+            face: Vec::new(),
+            offset: IVec3::ZERO,
+        }
+    }
+
+    pub fn place(&mut self, room: &Room) {
+        // the face spans the two axes it doesn't move along
+        let (across, up) = match self.data.direction {
+            CrusherDirection::North | CrusherDirection::South => (IVec3::X, IVec3::Y),
+            CrusherDirection::East | CrusherDirection::West => (IVec3::Z, IVec3::Y),
+            CrusherDirection::Up | CrusherDirection::Down => (IVec3::X, IVec3::Z),
+        };
+        self.face.clear();
+        for a in 0..self.data.width as i32 {
+            for b in 0..self.data.height as i32 {
+                let position = self.data.position + across * a + up * b;
+                let block = room.data.get_block(position);
+                if block != Block::Air {
+                    self.face.push((room.get_world_block_position(position), block.rotate(room.rotation)));
+                }
+            }
+        }
+        self.offset = self.data.direction.offset().rotate(room.rotation);
+    }
+
+    pub fn tick(&self, room: &Room, world: &mut World<Dungeon>, ticks: u32) {
+        let Some(ticks) = ticks.checked_sub(self.delay) else {
+            return;
+        };
+        let Some(motion) = motion(&self.data, ticks) else {
+            return;
+        };
+
+        match motion {
+            Motion::Extending { step, ticks: 0 } => {
+                for (position, block) in self.face.iter() {
+                    let from = position + self.offset * step as i32;
+                    self.move_block(world, from, from + self.offset, *block);
+                }
+            }
+            Motion::Retracting { step, ticks: 0 } => {
+                for (position, block) in self.face.iter() {
+                    let from = position + self.offset * (self.data.length - step) as i32;
+                    let IVec3 { x, y, z } = from;
+                    world.chunk_grid.set_block_at(Block::Air, x, y, z);
+                    self.move_block(world, from, from - self.offset, *block);
+                }
+            }
+            _ => {}
+        }
+
+        // anyone in the way of the layer moving out gets crushed
+        if let Motion::Extending { step, .. } = motion && !self.face.is_empty() {
+            let layer = self.offset * (step as i32 + 1);
+            let (min, max) = self.face.iter().fold((IVec3::MAX, IVec3::MIN), |(min, max), (position, _)| {
+                (min.min(position + layer), max.max(position + layer))
+            });
+            let aabb = AABB::new(min.as_dvec3(), max.as_dvec3() + 1.0);
+            for player in room.players() {
+                if !player.is_dead && player.collision_aabb().intersects(&aabb) {
+                    DungeonPlayer::kill(player, "was crushed");
+                }
+            }
+        }
+    }
+
+    fn move_block(&self, world: &mut World<Dungeon>, from: IVec3, to: IVec3, block: Block) {
+        let ticks = self.data.ticks_per_block;
+        world.spawn_entity(
+            from.as_dvec3() + dvec3(0.5, 0.0, 0.5),
+            0.0,
+            0.0,
+            BlockAppearance {
+                block,
+            },
+            (
+                MovingBlockBehaviour {
+                    block: to,
+                    replace_with: block,
+                    replace_in_tick: ticks,
+                    difference: self.offset.as_dvec3() / ticks as f64,
+                },
+                Lifetime {
+                    ticks,
+                },
+            ),
+        );
+        // blocks the way while the entity moves into place
+        if world.chunk_grid.get_block_at(to.x, to.y, to.z) == Block::Air {
+            world.chunk_grid.set_block_at(Block::Barrier, to.x, to.y, to.z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::ivec3;
+
+    #[test]
+    fn moves_out_and_back() {
+        let data = CrusherData {
+            position: ivec3(0, 0, 0),
+            direction: CrusherDirection::Up,
+            width: 1,
+            height: 1,
+            length: 2,
+            ticks_per_block: 5,
+            pause_ticks: 20,
+        };
+        assert_eq!(cycle_ticks(&data), 60);
+        assert_eq!(motion(&data, 0), None);
+        assert_eq!(motion(&data, 20), Some(Motion::Extending { step: 0, ticks: 0 }));
+        assert_eq!(motion(&data, 27), Some(Motion::Extending { step: 1, ticks: 2 }));
+        assert_eq!(motion(&data, 30), None);
+        assert_eq!(motion(&data, 50), Some(Motion::Retracting { step: 0, ticks: 0 }));
+        assert_eq!(motion(&data, 59), Some(Motion::Retracting { step: 1, ticks: 4 }));
+        assert_eq!(motion(&data, 60), None);
+        assert_eq!(motion(&data, 80), motion(&data, 20));
+    }
+
+    #[test]
+    fn zero_timings_dont_panic() {
+        let data = CrusherData {
+            position: ivec3(0, 0, 0),
+            direction: CrusherDirection::Up,
+            width: 1,
+            height: 1,
+            length: 0,
+            ticks_per_block: 0,
+            pause_ticks: 0,
+        };
+        assert_eq!(cycle_ticks(&data), 1);
+        let crusher = Crusher::new(data, 0);
+        for ticks in 0..10 {
+            motion(&crusher.data, ticks);
+        }
+    }
+}
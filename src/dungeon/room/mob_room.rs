@@ -0,0 +1,139 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::dungeon_mob::{DungeonMob, DungeonMobAppearance, MobKind};
+use crate::dungeon::entities::health::Health;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_data::{MobSpawn, RoomData, RoomType};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{ivec3, IVec3};
+use rand::prelude::IndexedRandom;
+use rand::seq::SliceRandom;
+use rand::{rng, Rng};
+use server::block::Block;
+use server::World;
+
+const STARRED_MOBS: [MobKind; 3] = [
+    MobKind::ZombieCommander,
+    MobKind::SkeletonMaster,
+    MobKind::Withermancer,
+];
+
+const REGULAR_MOBS: [MobKind; 2] = [
+    MobKind::ZombieSoldier,
+    MobKind::SkeletonSoldier,
+];
+
+// per segment of the room
+const STARRED_PER_SEGMENT: usize = 2;
+const REGULAR_PER_SEGMENT: usize = 1;
+
+pub struct MobRoom {
+    spawns: Vec<MobSpawn>,
+    required_mobs: Vec<Entity>,
+}
+
+impl MobRoom {
+    pub fn new(room_data: &RoomData, segment_count: usize) -> Self {
+        // rooms without mobs in their data get them placed on the floor,
+        // this happens on creation so its always the same for a given seed
+        let spawns = if !room_data.mobs.is_empty() {
+            room_data.mobs.clone()
+        } else if matches!(room_data.room_type, RoomType::Normal | RoomType::Rare | RoomType::Trap) {
+            generate_spawns(room_data, segment_count)
+        } else {
+            Vec::new()
+        };
+        Self {
+            spawns,
+            required_mobs: Vec::new(),
+        }
+    }
+}
+
+impl RoomImplementation for MobRoom {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for spawn in self.spawns.iter() {
+            let mut position = room.get_world_block_position(spawn.position).as_dvec3();
+            position.x += 0.5;
+            position.z += 0.5;
+
+            let mob = DungeonMob {
+                kind: spawn.kind,
+                starred: spawn.starred,
+            };
+            let health = Health::new(spawn.kind.max_health());
+            let entity = world.spawn_entity(
+                position,
+                rng().random_range(-180.0..180.0),
+                0.0,
+                DungeonMobAppearance {
+                    variant: spawn.kind.variant(),
+                    metadata: spawn.kind.metadata(),
+                    name: mob.display_name(health.current),
+                },
+                (mob, health),
+            );
+            if spawn.starred {
+                self.required_mobs.push(entity);
+            }
+        }
+
+        if self.required_mobs.is_empty() {
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+
+    fn on_entity_death(&mut self, room: &mut Room, world: &mut World<Dungeon>, entity: Entity) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        self.required_mobs.retain(|it| *it != entity);
+
+        if self.required_mobs.is_empty() {
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+}
+
+// picks random spots on the lowest floor of the room, that a mob can stand in
+fn generate_spawns(room_data: &RoomData, segment_count: usize) -> Vec<MobSpawn> {
+    let mut spots: Vec<IVec3> = Vec::new();
+
+    // stay away from the edges, where the doors and walls are
+    for x in 3..room_data.width - 3 {
+        for z in 3..room_data.length - 3 {
+            for y in room_data.bottom + 1..room_data.bottom + room_data.height - 1 {
+                let is_floor = room_data.get_block(ivec3(x, y - 1, z)) != Block::Air
+                    && room_data.get_block(ivec3(x, y, z)) == Block::Air
+                    && room_data.get_block(ivec3(x, y + 1, z)) == Block::Air;
+                if is_floor {
+                    spots.push(ivec3(x, y, z));
+                    break;
+                }
+            }
+        }
+    }
+
+    let mut rng = seeded_rng();
+    spots.shuffle(&mut rng);
+
+    let starred = segment_count * STARRED_PER_SEGMENT;
+    let regular = segment_count * REGULAR_PER_SEGMENT;
+
+    spots.into_iter()
+        .take(starred + regular)
+        .enumerate()
+        .map(|(index, position)| {
+            let starred = index < starred;
+            let kinds = if starred { STARRED_MOBS.as_slice() } else { REGULAR_MOBS.as_slice() };
+            MobSpawn {
+                kind: *kinds.choose(&mut rng).unwrap(),
+                position,
+                starred,
+            }
+        })
+        .collect()
+}
@@ -1,4 +1,10 @@
 pub mod room;
 pub mod room_data;
+pub mod secrets;
 pub mod room_implementation;
+pub mod mob_room;
+pub mod blood_room;
+pub mod breakable;
+pub mod crusher;
+pub mod trap_room;
 pub mod puzzles;
\ No newline at end of file
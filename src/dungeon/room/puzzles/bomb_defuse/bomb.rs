@@ -0,0 +1,205 @@
+use crate::dungeon::dungeon::{format_time, Dungeon};
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::puzzles::bomb_defuse::button::ButtonModule;
+use crate::dungeon::room::puzzles::bomb_defuse::colours::ColoursModule;
+use crate::dungeon::room::puzzles::bomb_defuse::numbers::NumbersModule;
+use crate::dungeon::room::puzzles::bomb_defuse::wires::WiresModule;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use glam::{ivec3, vec3, IVec3, Vec3};
+use server::block::block_parameter::ButtonDirection;
+use server::block::metadata::BlockMetadata;
+use server::block::rotatable::Rotate;
+use server::block::Block;
+use server::constants::{Particle, Sound};
+use server::network::protocol::play::clientbound::Chat;
+use server::{Player, World};
+
+// every module is a square panel, with its light on top
+pub const MODULE_SIZE: i32 = 5;
+pub const PANEL_BLOCK: Block = Block::IronBlock;
+
+// bottom left of each module, the panels face +z and go along x
+const MODULE_ORIGINS: [IVec3; 4] = [ivec3(3, 69, 6), ivec3(10, 69, 6), ivec3(17, 69, 6), ivec3(24, 69, 6)];
+
+const TIME_LIMIT: usize = 20 * 180;
+const STRIKE_PENALTY: usize = 20 * 30;
+
+#[derive(Debug, PartialEq)]
+pub enum ModuleResult {
+    // not a part of the module that does anything
+    Ignored,
+    Progress,
+    Solved,
+    Strike,
+}
+
+/// one part of the bomb. cells are relative to the bottom left of the panel,
+/// x goes right, y goes up and z 1 is in front of the panel
+pub trait BombModule {
+    // sent to players when the room is discovered
+    fn manual(&self) -> &'static str;
+
+    /// blocks that differ from the empty panel
+    fn blocks(&self) -> Vec<(IVec3, Block)>;
+
+    fn interact(&mut self, cell: IVec3, seconds_left: usize) -> ModuleResult;
+
+    fn is_solved(&self) -> bool;
+}
+
+/// a button on the front of the panel
+pub fn panel_button() -> Block {
+    Block::StoneButton {
+        direction: ButtonDirection::from_meta(3),
+        powered: false,
+    }
+}
+
+pub struct BombDefusePuzzle {
+    modules: Vec<Box<dyn BombModule>>,
+    ticks_left: usize,
+}
+
+impl Default for BombDefusePuzzle {
+    fn default() -> Self {
+        let mut rng = seeded_rng();
+        Self {
+            modules: vec![
+                Box::new(WiresModule::new(&mut rng)),
+                Box::new(NumbersModule::new(&mut rng)),
+                Box::new(ColoursModule::new(&mut rng)),
+                Box::new(ButtonModule::new(&mut rng)),
+            ],
+            ticks_left: TIME_LIMIT,
+        }
+    }
+}
+
+impl BombDefusePuzzle {
+    fn draw_module(&self, index: usize, room: &Room, world: &mut World<Dungeon>) {
+        let module = &self.modules[index];
+        let mut set_block = |cell: IVec3, block: Block| {
+            let IVec3 { x, y, z } = room.get_world_block_position(MODULE_ORIGINS[index] + cell);
+            world.chunk_grid.set_block_at(block.rotate(room.rotation), x, y, z);
+        };
+        for x in 0..MODULE_SIZE {
+            for y in 0..MODULE_SIZE {
+                set_block(ivec3(x, y, 0), PANEL_BLOCK);
+                set_block(ivec3(x, y, 1), Block::Air);
+            }
+        }
+        for (cell, block) in module.blocks() {
+            set_block(cell, block);
+        }
+        let light = if module.is_solved() { Block::LitRedstoneLamp } else { Block::RedstoneLamp };
+        set_block(ivec3(MODULE_SIZE / 2, MODULE_SIZE, 0), light);
+    }
+
+    fn find_cell(&self, room: &Room, position: IVec3) -> Option<(usize, IVec3)> {
+        self.modules.iter().enumerate().find_map(|(index, module)| {
+            module.blocks()
+                .into_iter()
+                .map(|(cell, _)| cell)
+                .find(|cell| room.get_world_block_position(MODULE_ORIGINS[index] + *cell) == position)
+                .map(|cell| (index, cell))
+        })
+    }
+}
+
+impl RoomImplementation for BombDefusePuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for index in 0..self.modules.len() {
+            self.draw_module(index, room, world);
+        }
+        for player in room.players() {
+            player.send_message("§eDefuse the §cBomb §ebefore the timer runs out!");
+            for module in self.modules.iter() {
+                player.send_message(module.manual());
+            }
+        }
+    }
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+
+        if self.ticks_left.is_multiple_of(10) {
+            let time = format_time(self.ticks_left);
+            for player in room.players() {
+                player.send_action_bar(&format!("§cBomb: §e{time}"));
+            }
+        }
+        if self.ticks_left != 0 {
+            return;
+        }
+
+        let center = room.get_world_block_position(MODULE_ORIGINS[1] + ivec3(MODULE_SIZE, 2, 1));
+        world.play_sound_at(Sound::RandomExplode, 4.0, 1.0, center.as_dvec3());
+        world.spawn_particle(Particle::HugeExplosion, center.as_vec3() + vec3(0.5, 0.5, 0.5), Vec3::ZERO, 1);
+        world.write_global_packet(&Chat::new("§cPUZZLE FAIL! §eThe §cBomb §ewent off! §4Y§ci§6k§ee§as§2!"));
+        room.status = RoomStatus::Failed;
+        world.map.draw_checkmark(room);
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        let Some((index, cell)) = self.find_cell(room, position) else {
+            return;
+        };
+        if self.modules[index].is_solved() {
+            return;
+        }
+
+        let result = self.modules[index].interact(cell, self.ticks_left / 20);
+        self.draw_module(index, room, player.world_mut());
+        match result {
+            ModuleResult::Ignored => {}
+            ModuleResult::Progress => player.play_sound_at(Sound::RandomWoodClick, 1.0, 1.0, position.as_dvec3()),
+            ModuleResult::Strike => {
+                self.ticks_left = self.ticks_left.saturating_sub(STRIKE_PENALTY);
+                player.play_sound_at(Sound::NoteHarp, 1.0, 0.5, position.as_dvec3());
+                player.send_message(&format!("§cStrike! §7The timer lost {} seconds.", STRIKE_PENALTY / 20));
+            }
+            ModuleResult::Solved => player.play_sound_at(Sound::NoteHarp, 1.0, 2.0, position.as_dvec3()),
+        }
+
+        if self.modules.iter().all(|module| module.is_solved()) {
+            let world = player.world_mut();
+            world.write_global_packet(&Chat::new(
+                &format!("§aPUZZLE SOLVED! §7{} §edefused the §cBomb§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+            ));
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::SeededRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn modules_fit_their_panels() {
+        for seed in 0..20 {
+            SeededRng::set_seed(seed);
+            for module in BombDefusePuzzle::default().modules.iter() {
+                let blocks = module.blocks();
+                let cells = blocks.iter().map(|(cell, _)| *cell).collect::<HashSet<IVec3>>();
+                assert_eq!(cells.len(), blocks.len(), "{}", module.manual());
+                for cell in cells {
+                    assert!(cell.x >= 0 && cell.x < MODULE_SIZE, "{cell}");
+                    assert!(cell.y >= 0 && cell.y < MODULE_SIZE, "{cell}");
+                    assert!(cell.z == 0 || cell.z == 1, "{cell}");
+                }
+            }
+        }
+    }
+}
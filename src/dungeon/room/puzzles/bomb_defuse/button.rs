@@ -0,0 +1,75 @@
+use crate::dungeon::room::puzzles::bomb_defuse::bomb::{panel_button, BombModule, ModuleResult, MODULE_SIZE};
+use glam::{ivec3, IVec3};
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use server::block::block_parameter::BlockColor;
+use server::block::Block;
+
+// the colour of the strip, and the digit the timer's seconds have to end in
+const STRIPS: [(BlockColor, usize); 3] = [(BlockColor::Red, 1), (BlockColor::Blue, 4), (BlockColor::Yellow, 5)];
+
+const BUTTON: IVec3 = ivec3(2, 2, 1);
+
+/// one button, that has to be pressed at the right time
+pub struct ButtonModule {
+    strip: BlockColor,
+    digit: usize,
+    solved: bool,
+}
+
+impl ButtonModule {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let (strip, digit) = *STRIPS.choose(rng).unwrap();
+        Self {
+            strip,
+            digit,
+            solved: false,
+        }
+    }
+}
+
+impl BombModule for ButtonModule {
+    fn manual(&self) -> &'static str {
+        "§eButton: §7press it when the timer's seconds end in 1 for a red strip, 4 for blue and 5 for yellow."
+    }
+
+    fn blocks(&self) -> Vec<(IVec3, Block)> {
+        let mut blocks = vec![(BUTTON, panel_button())];
+        for y in 0..MODULE_SIZE {
+            blocks.push((ivec3(MODULE_SIZE - 1, y, 0), Block::Wool { color: self.strip }));
+        }
+        blocks
+    }
+
+    fn interact(&mut self, cell: IVec3, seconds_left: usize) -> ModuleResult {
+        if cell != BUTTON {
+            return ModuleResult::Ignored;
+        }
+        if seconds_left % 10 != self.digit {
+            return ModuleResult::Strike;
+        }
+        self.solved = true;
+        ModuleResult::Solved
+    }
+
+    fn is_solved(&self) -> bool {
+        self.solved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressed_on_the_right_digit() {
+        let mut button = ButtonModule {
+            strip: BlockColor::Blue,
+            digit: 4,
+            solved: false,
+        };
+        assert_eq!(button.interact(ivec3(4, 0, 0), 94), ModuleResult::Ignored);
+        assert_eq!(button.interact(BUTTON, 95), ModuleResult::Strike);
+        assert_eq!(button.interact(BUTTON, 84), ModuleResult::Solved);
+    }
+}
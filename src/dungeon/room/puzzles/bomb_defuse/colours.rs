@@ -0,0 +1,97 @@
+use crate::dungeon::room::puzzles::bomb_defuse::bomb::{BombModule, ModuleResult, PANEL_BLOCK};
+use glam::{ivec3, IVec3};
+use rand::prelude::IndexedRandom;
+use rand::Rng;
+use server::block::block_parameter::BlockColor;
+use server::block::Block;
+
+const ROUNDS: usize = 3;
+
+// the display is above the middle, with the choices along the bottom
+const DISPLAY: IVec3 = ivec3(2, 3, 0);
+const CHOICES: [(IVec3, BlockColor); 4] = [
+    (ivec3(0, 1, 0), BlockColor::Red),
+    (ivec3(1, 1, 0), BlockColor::Green),
+    (ivec3(3, 1, 0), BlockColor::Blue),
+    (ivec3(4, 1, 0), BlockColor::Yellow),
+];
+
+fn pair(colour: BlockColor) -> BlockColor {
+    match colour {
+        BlockColor::Red => BlockColor::Green,
+        BlockColor::Green => BlockColor::Red,
+        BlockColor::Blue => BlockColor::Yellow,
+        _ => BlockColor::Blue,
+    }
+}
+
+/// shows a colour every round, the player has to click the colour it pairs with
+pub struct ColoursModule {
+    shown: [BlockColor; ROUNDS],
+    round: usize,
+}
+
+impl ColoursModule {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let colours = CHOICES.map(|(_, colour)| colour);
+        Self {
+            shown: [(); ROUNDS].map(|_| *colours.choose(rng).unwrap()),
+            round: 0,
+        }
+    }
+}
+
+impl BombModule for ColoursModule {
+    fn manual(&self) -> &'static str {
+        "§eColours: §7click the pair of the shown colour, red goes with green and blue with yellow."
+    }
+
+    fn blocks(&self) -> Vec<(IVec3, Block)> {
+        let display = match self.shown.get(self.round) {
+            Some(colour) => Block::Wool { color: *colour },
+            None => PANEL_BLOCK,
+        };
+        let mut blocks = vec![(DISPLAY, display)];
+        for (cell, colour) in CHOICES {
+            blocks.push((cell, Block::Wool { color: colour }));
+        }
+        blocks
+    }
+
+    fn interact(&mut self, cell: IVec3, _seconds_left: usize) -> ModuleResult {
+        let Some((_, colour)) = CHOICES.iter().find(|(choice, _)| *choice == cell) else {
+            return ModuleResult::Ignored;
+        };
+        if *colour != pair(self.shown[self.round]) {
+            return ModuleResult::Strike;
+        }
+        self.round += 1;
+        if self.is_solved() {
+            ModuleResult::Solved
+        } else {
+            ModuleResult::Progress
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.round == ROUNDS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicks_the_pair() {
+        let mut colours = ColoursModule {
+            shown: [BlockColor::Red, BlockColor::Yellow, BlockColor::Red],
+            round: 0,
+        };
+        assert_eq!(colours.interact(DISPLAY, 0), ModuleResult::Ignored);
+        assert_eq!(colours.interact(ivec3(0, 1, 0), 0), ModuleResult::Strike);
+        assert_eq!(colours.interact(ivec3(1, 1, 0), 0), ModuleResult::Progress);
+        assert_eq!(colours.interact(ivec3(3, 1, 0), 0), ModuleResult::Progress);
+        assert_eq!(colours.interact(ivec3(1, 1, 0), 0), ModuleResult::Solved);
+    }
+}
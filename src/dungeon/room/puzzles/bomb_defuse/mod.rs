@@ -0,0 +1,5 @@
+pub mod bomb;
+pub mod wires;
+pub mod numbers;
+pub mod colours;
+pub mod button;
\ No newline at end of file
@@ -0,0 +1,88 @@
+use crate::dungeon::room::puzzles::bomb_defuse::bomb::{panel_button, BombModule, ModuleResult, MODULE_SIZE};
+use glam::{ivec3, IVec3};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use server::block::Block;
+
+const COLUMNS: usize = MODULE_SIZE as usize;
+
+/// quartz columns of different heights, with a button at the bottom of each
+pub struct NumbersModule {
+    heights: [i32; COLUMNS],
+    pressed: i32,
+}
+
+impl NumbersModule {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let mut heights = [0; COLUMNS];
+        for (index, height) in heights.iter_mut().enumerate() {
+            *height = index as i32 + 1;
+        }
+        heights.shuffle(rng);
+        Self {
+            heights,
+            pressed: 0,
+        }
+    }
+}
+
+impl BombModule for NumbersModule {
+    fn manual(&self) -> &'static str {
+        "§eNumbers: §7press the buttons from the shortest column to the tallest."
+    }
+
+    fn blocks(&self) -> Vec<(IVec3, Block)> {
+        let mut blocks = Vec::new();
+        for (x, height) in self.heights.iter().enumerate() {
+            for y in 0..*height {
+                blocks.push((ivec3(x as i32, y, 0), Block::Quartz));
+            }
+            blocks.push((ivec3(x as i32, 0, 1), panel_button()));
+        }
+        blocks
+    }
+
+    fn interact(&mut self, cell: IVec3, _seconds_left: usize) -> ModuleResult {
+        if cell.z != 1 {
+            return ModuleResult::Ignored;
+        }
+        // a wrong press starts it over
+        if self.heights[cell.x as usize] != self.pressed + 1 {
+            self.pressed = 0;
+            return ModuleResult::Strike;
+        }
+        self.pressed += 1;
+        if self.is_solved() {
+            ModuleResult::Solved
+        } else {
+            ModuleResult::Progress
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.pressed == COLUMNS as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presses_in_order() {
+        let mut numbers = NumbersModule {
+            heights: [3, 1, 5, 2, 4],
+            pressed: 0,
+        };
+        assert_eq!(numbers.interact(ivec3(1, 0, 0), 0), ModuleResult::Ignored);
+        assert_eq!(numbers.interact(ivec3(1, 0, 1), 0), ModuleResult::Progress);
+        assert_eq!(numbers.interact(ivec3(3, 0, 1), 0), ModuleResult::Progress);
+        assert_eq!(numbers.interact(ivec3(2, 0, 1), 0), ModuleResult::Strike);
+        assert_eq!(numbers.pressed, 0);
+
+        for x in [1, 3, 0, 4] {
+            assert_eq!(numbers.interact(ivec3(x, 0, 1), 0), ModuleResult::Progress);
+        }
+        assert_eq!(numbers.interact(ivec3(2, 0, 1), 0), ModuleResult::Solved);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::dungeon::room::puzzles::bomb_defuse::bomb::{BombModule, ModuleResult, MODULE_SIZE};
+use glam::{ivec3, IVec3};
+use rand::prelude::{IndexedRandom, SliceRandom};
+use rand::Rng;
+use server::block::block_parameter::BlockColor;
+use server::block::Block;
+
+const COLOURS: [BlockColor; 5] = [BlockColor::Red, BlockColor::Blue, BlockColor::Yellow, BlockColor::White, BlockColor::Black];
+
+// wires hang in front of the panel, one per column
+const WIRE_LENGTH: i32 = 3;
+const WIRE_BOTTOM: i32 = 1;
+
+/// every colour is repeated, except for the wire that has to be cut
+pub struct WiresModule {
+    wires: Vec<BlockColor>,
+    cut: Vec<bool>,
+    solved: bool,
+}
+
+impl WiresModule {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        let count = rng.random_range(4..=MODULE_SIZE as usize);
+        let mut colours = COLOURS;
+        colours.shuffle(rng);
+        let [unique, repeated @ ..] = colours;
+
+        // at least two of each repeated colour, so only one can be on its own
+        let kinds = (count - 1) / 2;
+        let mut wires = vec![unique];
+        for index in 0..count - 1 {
+            let colour = match repeated.get(index / 2) {
+                Some(colour) if index / 2 < kinds => *colour,
+                _ => *repeated[..kinds].choose(rng).unwrap(),
+            };
+            wires.push(colour);
+        }
+        wires.shuffle(rng);
+
+        Self {
+            cut: vec![false; wires.len()],
+            wires,
+            solved: false,
+        }
+    }
+
+    fn is_correct(&self, index: usize) -> bool {
+        self.wires.iter().filter(|colour| **colour == self.wires[index]).count() == 1
+    }
+}
+
+impl BombModule for WiresModule {
+    fn manual(&self) -> &'static str {
+        "§eWires: §7cut the only wire of its colour."
+    }
+
+    fn blocks(&self) -> Vec<(IVec3, Block)> {
+        let mut blocks = Vec::new();
+        for (index, colour) in self.wires.iter().enumerate() {
+            for y in WIRE_BOTTOM..WIRE_BOTTOM + WIRE_LENGTH {
+                // a cut wire is missing its middle
+                if self.cut[index] && y == WIRE_BOTTOM + WIRE_LENGTH / 2 {
+                    continue;
+                }
+                blocks.push((ivec3(index as i32, y, 1), Block::Wool { color: *colour }));
+            }
+        }
+        blocks
+    }
+
+    fn interact(&mut self, cell: IVec3, _seconds_left: usize) -> ModuleResult {
+        let index = cell.x as usize;
+        if index >= self.wires.len() || self.cut[index] {
+            return ModuleResult::Ignored;
+        }
+        self.cut[index] = true;
+        if self.is_correct(index) {
+            self.solved = true;
+            ModuleResult::Solved
+        } else {
+            ModuleResult::Strike
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.solved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::{seeded_rng, SeededRng};
+
+    #[test]
+    fn one_wire_is_unique() {
+        for seed in 0..50 {
+            SeededRng::set_seed(seed);
+            let wires = WiresModule::new(&mut seeded_rng());
+            let correct = (0..wires.wires.len()).filter(|index| wires.is_correct(*index)).count();
+            assert_eq!(correct, 1, "seed {seed}: {:?}", wires.wires);
+        }
+    }
+
+    #[test]
+    fn cutting_wires() {
+        let mut wires = WiresModule {
+            wires: vec![BlockColor::Red, BlockColor::Blue, BlockColor::Red, BlockColor::Red],
+            cut: vec![false; 4],
+            solved: false,
+        };
+        assert_eq!(wires.interact(ivec3(0, 2, 1), 0), ModuleResult::Strike);
+        assert_eq!(wires.interact(ivec3(0, 1, 1), 0), ModuleResult::Ignored);
+        assert_eq!(wires.interact(ivec3(4, 1, 1), 0), ModuleResult::Ignored);
+        assert_eq!(wires.interact(ivec3(1, 3, 1), 0), ModuleResult::Solved);
+        assert!(wires.is_solved());
+    }
+}
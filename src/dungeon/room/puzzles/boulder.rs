@@ -0,0 +1,300 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::entities::block_appearance::BlockAppearance;
+use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::entities::moving_block_behaviour::MovingBlockBehaviour;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use glam::{dvec3, ivec2, ivec3, IVec2, IVec3};
+use rand::prelude::IndexedRandom;
+use server::block::block_parameter::ButtonDirection;
+use server::block::metadata::BlockMetadata;
+use server::block::rotatable::Rotate;
+use server::block::Block;
+use server::constants::Sound;
+use server::network::protocol::play::clientbound::Chat;
+use server::{Player, World};
+use std::collections::{HashMap, VecDeque};
+
+// boulder layouts, 7 columns by 6 rows. row 0 is by the chest, players come in from below the last row
+const PATTERNS: &str = include_str!("../../../../data/puzzles/boulder.txt");
+
+const COLUMNS: i32 = 7;
+const ROWS: i32 = 6;
+const BOULDER_SIZE: i32 = 3;
+
+// corner of the cell in column 0, row 0. columns go along x and rows along z
+const GRID_ORIGIN: IVec3 = ivec3(5, 69, 8);
+// the cell in front of the chest
+const EXIT: IVec2 = ivec2(3, 0);
+
+const BOULDER_BLOCK: Block = Block::Cobblestone;
+
+const PUSH_TICKS: u32 = 10;
+
+const DIRECTIONS: [IVec2; 4] = [ivec2(0, -1), ivec2(1, 0), ivec2(0, 1), ivec2(-1, 0)];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Grid([[bool; COLUMNS as usize]; ROWS as usize]);
+
+impl Grid {
+    fn in_bounds(cell: IVec2) -> bool {
+        cell.x >= 0 && cell.x < COLUMNS && cell.y >= 0 && cell.y < ROWS
+    }
+
+    fn has_boulder(&self, cell: IVec2) -> bool {
+        Self::in_bounds(cell) && self.0[cell.y as usize][cell.x as usize]
+    }
+
+    fn set(&mut self, cell: IVec2, boulder: bool) {
+        self.0[cell.y as usize][cell.x as usize] = boulder;
+    }
+
+    fn boulders(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..ROWS)
+            .flat_map(|row| (0..COLUMNS).map(move |column| ivec2(column, row)))
+            .filter(|cell| self.has_boulder(*cell))
+    }
+
+    // empty cells players can walk to from the entrance
+    fn reachable(&self) -> [[bool; COLUMNS as usize]; ROWS as usize] {
+        let mut reachable = [[false; COLUMNS as usize]; ROWS as usize];
+        let mut queue = (0..COLUMNS).map(|column| ivec2(column, ROWS - 1)).collect::<VecDeque<IVec2>>();
+        while let Some(cell) = queue.pop_front() {
+            if !Self::in_bounds(cell) || self.has_boulder(cell) || reachable[cell.y as usize][cell.x as usize] {
+                continue;
+            }
+            reachable[cell.y as usize][cell.x as usize] = true;
+            queue.extend(DIRECTIONS.map(|direction| cell + direction));
+        }
+        reachable
+    }
+
+    fn is_solved(&self) -> bool {
+        self.reachable()[EXIT.y as usize][EXIT.x as usize]
+    }
+
+    // players push from the opposite side, which is either somewhere they can walk to or the entrance
+    fn can_push(&self, boulder: IVec2, direction: IVec2) -> bool {
+        let standing = boulder - direction;
+        let can_stand = if Self::in_bounds(standing) {
+            self.reachable()[standing.y as usize][standing.x as usize]
+        } else {
+            standing.y == ROWS
+        };
+        let destination = boulder + direction;
+        can_stand && Self::in_bounds(destination) && !self.has_boulder(destination)
+    }
+}
+
+fn parse_patterns() -> Vec<Grid> {
+    PATTERNS.split("\n\n")
+        .map(|pattern| {
+            let mut grid = Grid([[false; COLUMNS as usize]; ROWS as usize]);
+            for (row, line) in pattern.lines().enumerate() {
+                for (column, char) in line.chars().enumerate() {
+                    grid.0[row][column] = char == '#';
+                }
+            }
+            grid
+        })
+        .collect()
+}
+
+fn cell_corner(cell: IVec2) -> IVec3 {
+    GRID_ORIGIN + ivec3(cell.x, 0, cell.y) * BOULDER_SIZE
+}
+
+fn boulder_blocks(cell: IVec2) -> impl Iterator<Item = IVec3> {
+    let corner = cell_corner(cell);
+    (0..BOULDER_SIZE).flat_map(move |x| {
+        (0..BOULDER_SIZE).flat_map(move |y| (0..BOULDER_SIZE).map(move |z| corner + ivec3(x, y, z)))
+    })
+}
+
+// the button sits in the middle of the face opposite to where the boulder gets pushed
+fn button_position(boulder: IVec2, direction: IVec2) -> IVec3 {
+    let center = cell_corner(boulder) + IVec3::splat(BOULDER_SIZE / 2);
+    center - ivec3(direction.x, 0, direction.y) * (BOULDER_SIZE / 2 + 1)
+}
+
+fn button_block(direction: IVec2) -> Block {
+    // buttons face away from the block they are on
+    let meta = match (-direction).to_array() {
+        [1, 0] => 1,
+        [-1, 0] => 2,
+        [0, 1] => 3,
+        _ => 4,
+    };
+    Block::StoneButton {
+        direction: ButtonDirection::from_meta(meta),
+        powered: false,
+    }
+}
+
+pub struct BoulderPuzzle {
+    grid: Grid,
+    // world position of each button, and the boulder and direction it pushes
+    buttons: HashMap<IVec3, (IVec2, IVec2)>,
+    ticks: usize,
+    moving_until: usize,
+}
+
+impl Default for BoulderPuzzle {
+    fn default() -> Self {
+        let patterns = parse_patterns();
+        Self {
+            grid: *patterns.choose(&mut seeded_rng()).unwrap(),
+            buttons: HashMap::new(),
+            ticks: 0,
+            moving_until: 0,
+        }
+    }
+}
+
+impl RoomImplementation for BoulderPuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for boulder in self.grid.boulders() {
+            for position in boulder_blocks(boulder) {
+                let IVec3 { x, y, z } = room.get_world_block_position(position);
+                world.chunk_grid.set_block_at(BOULDER_BLOCK, x, y, z);
+            }
+        }
+        self.place_buttons(room, world);
+    }
+
+    fn tick(&mut self, _room: &mut Room, _world: &mut World<Dungeon>) {
+        self.ticks += 1;
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) || self.ticks < self.moving_until {
+            return;
+        }
+        let Some((boulder, direction)) = self.buttons.get(&position).copied() else {
+            return;
+        };
+        if !self.grid.can_push(boulder, direction) {
+            player.play_sound_at(Sound::NoteHarp, 1.0, 0.5, position.as_dvec3());
+            return;
+        }
+
+        let world = player.world_mut();
+        let destination = boulder + direction;
+        self.clear_buttons(world);
+        self.grid.set(boulder, false);
+        self.grid.set(destination, true);
+        self.moving_until = self.ticks + PUSH_TICKS as usize;
+
+        for (from, to) in boulder_blocks(boulder).zip(boulder_blocks(destination)) {
+            let from = room.get_world_block_position(from);
+            let to = room.get_world_block_position(to);
+            world.chunk_grid.set_block_at(Block::Air, from.x, from.y, from.z);
+            world.chunk_grid.set_block_at(Block::Barrier, to.x, to.y, to.z);
+            world.spawn_entity(
+                from.as_dvec3() + dvec3(0.5, 0.0, 0.5),
+                0.0,
+                0.0,
+                BlockAppearance {
+                    block: BOULDER_BLOCK,
+                },
+                (
+                    MovingBlockBehaviour {
+                        block: to,
+                        replace_with: BOULDER_BLOCK,
+                        replace_in_tick: PUSH_TICKS,
+                        difference: (to - from).as_dvec3() / PUSH_TICKS as f64,
+                    },
+                    Lifetime {
+                        ticks: PUSH_TICKS,
+                    }
+                )
+            );
+        }
+        self.place_buttons(room, world);
+        player.play_sound_at(Sound::RandomWoodClick, 1.0, 0.6, position.as_dvec3());
+
+        if self.grid.is_solved() {
+            world.write_global_packet(&Chat::new(
+                &format!("§aPUZZLE SOLVED! §7{} §ecleared a path through the §bBoulders§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+            ));
+            self.clear_buttons(world);
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+}
+
+impl BoulderPuzzle {
+    fn place_buttons(&mut self, room: &Room, world: &mut World<Dungeon>) {
+        for boulder in self.grid.boulders() {
+            for direction in DIRECTIONS {
+                let standing = boulder - direction;
+                if self.grid.has_boulder(standing) || (!Grid::in_bounds(standing) && standing.y != ROWS) {
+                    continue;
+                }
+                let position = room.get_world_block_position(button_position(boulder, direction));
+                let block = button_block(direction).rotate(room.rotation);
+                world.chunk_grid.set_block_at(block, position.x, position.y, position.z);
+                self.buttons.insert(position, (boulder, direction));
+            }
+        }
+    }
+
+    fn clear_buttons(&mut self, world: &mut World<Dungeon>) {
+        for (position, _) in self.buttons.drain() {
+            world.chunk_grid.set_block_at(Block::Air, position.x, position.y, position.z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // breadth first search over every push players can make
+    fn solve(grid: Grid) -> Option<usize> {
+        let mut seen = HashSet::from([grid]);
+        let mut queue = VecDeque::from([(grid, 0)]);
+        while let Some((grid, pushes)) = queue.pop_front() {
+            if grid.is_solved() {
+                return Some(pushes);
+            }
+            for boulder in grid.boulders() {
+                for direction in DIRECTIONS {
+                    if !grid.can_push(boulder, direction) {
+                        continue;
+                    }
+                    let mut next = grid;
+                    next.set(boulder, false);
+                    next.set(boulder + direction, true);
+                    if seen.insert(next) {
+                        queue.push_back((next, pushes + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn every_pattern_is_solvable() {
+        let patterns = parse_patterns();
+        assert!(!patterns.is_empty());
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            assert!(!pattern.is_solved(), "pattern {index} starts solved");
+            assert!(solve(pattern).is_some(), "pattern {index} can't be solved");
+        }
+    }
+
+    #[test]
+    fn buttons_are_outside_the_boulder() {
+        for direction in DIRECTIONS {
+            let button = button_position(ivec2(2, 2), direction);
+            assert!(boulder_blocks(ivec2(2, 2)).all(|block| block != button));
+            assert!(boulder_blocks(ivec2(2, 2) - direction).any(|block| block == button));
+        }
+    }
+}
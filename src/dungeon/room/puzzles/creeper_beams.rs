@@ -0,0 +1,240 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, DVec3, IVec3};
+use rand::prelude::SliceRandom;
+use server::block::Block;
+use server::constants::{EntityVariant, Particle, Sound};
+use server::entity::components::MobAppearance;
+use server::entity::entity_metadata::EntityMetadata;
+use server::network::binary::var_int::VarInt;
+use server::network::protocol::play::clientbound::Chat;
+use server::types::aabb::AABB;
+use server::{ClientId, Player, World};
+use std::collections::HashMap;
+
+const CREEPER_POSITION: IVec3 = ivec3(15, 69, 15);
+const CREEPER_HITBOX: AABB = AABB::from_width_height(0.6, 1.7);
+
+const PAIRS: usize = 4;
+
+// lanterns are placed on a ring around the creeper, this far out
+const RING_DISTANCE: i32 = 12;
+const LANTERN_HEIGHTS: [i32; 3] = [69, 70, 71];
+
+const BEAM_INTERVAL: usize = 10;
+
+pub struct CreeperBeamsPuzzle {
+    lanterns: Vec<[IVec3; 2]>,
+    beams: Vec<(DVec3, DVec3)>,
+    selected: HashMap<ClientId, IVec3>,
+    creeper: Option<Entity>,
+    ticks: usize,
+}
+
+fn creeper_hitbox(creeper: IVec3) -> AABB {
+    CREEPER_HITBOX.offset(creeper.as_dvec3() + dvec3(0.5, 0.0, 0.5))
+}
+
+fn block_center(position: IVec3) -> DVec3 {
+    position.as_dvec3() + DVec3::splat(0.5)
+}
+
+fn passes_through_creeper(creeper: IVec3, a: IVec3, b: IVec3) -> bool {
+    creeper_hitbox(creeper).intersects_segment(block_center(a), block_center(b))
+}
+
+// picks lantern pairs opposite each other around the creeper,
+// making sure that no two lanterns line up with the creeper unless they are a pair
+fn generate_lanterns() -> Vec<[IVec3; 2]> {
+    let rng = &mut seeded_rng();
+
+    let center = CREEPER_POSITION;
+    let mut spots: Vec<IVec3> = Vec::new();
+    for x in -RING_DISTANCE..=RING_DISTANCE {
+        for z in -RING_DISTANCE..=RING_DISTANCE {
+            let on_ring = x.abs() == RING_DISTANCE || z.abs() == RING_DISTANCE;
+            // only one half of the ring, the other lantern of a pair goes on the other half
+            let first_half = z < 0 || (z == 0 && x < 0);
+            if on_ring && first_half {
+                spots.push(ivec3(center.x + x, 0, center.z + z));
+            }
+        }
+    }
+    spots.shuffle(rng);
+
+    let mut lanterns: Vec<[IVec3; 2]> = Vec::new();
+    for spot in spots {
+        if lanterns.len() == PAIRS {
+            break;
+        }
+        let mut heights = LANTERN_HEIGHTS.iter()
+            .flat_map(|&a| LANTERN_HEIGHTS.iter().map(move |&b| (a, b)))
+            .collect::<Vec<(i32, i32)>>();
+        heights.shuffle(rng);
+
+        let pair = heights.into_iter()
+            .map(|(y_a, y_b)| [
+                ivec3(spot.x, y_a, spot.z),
+                ivec3(center.x * 2 - spot.x, y_b, center.z * 2 - spot.z),
+            ])
+            .find(|[a, b]| {
+                passes_through_creeper(center, *a, *b) && lanterns.iter().flatten().all(|other| {
+                    !passes_through_creeper(center, *a, *other) && !passes_through_creeper(center, *b, *other)
+                })
+            });
+
+        if let Some(pair) = pair {
+            lanterns.push(pair);
+        }
+    }
+    lanterns
+}
+
+// block dust takes the block id with its metadata in the upper bits
+fn block_dust_argument(block: Block) -> VarInt {
+    let state = block.get_blockstate_id() as i32;
+    VarInt((state >> 4) | ((state & 15) << 12))
+}
+
+impl Default for CreeperBeamsPuzzle {
+    fn default() -> Self {
+        Self {
+            lanterns: generate_lanterns(),
+            beams: Vec::new(),
+            selected: HashMap::new(),
+            creeper: None,
+            ticks: 0,
+        }
+    }
+}
+
+impl RoomImplementation for CreeperBeamsPuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        // convert lanterns to absolute positions
+        for pair in self.lanterns.iter_mut() {
+            for lantern in pair.iter_mut() {
+                *lantern = room.get_world_block_position(*lantern);
+                world.chunk_grid.set_block_at(Block::SeaLantern, lantern.x, lantern.y, lantern.z);
+            }
+        }
+
+        let position = room.get_world_block_position(CREEPER_POSITION).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+        self.creeper = Some(world.spawn_entity(
+            position,
+            0.0,
+            0.0,
+            MobAppearance {
+                variant: EntityVariant::Creeper,
+                metadata: EntityMetadata::Creeper(Default::default()),
+            },
+            (),
+        ));
+    }
+
+    fn tick(&mut self, _room: &mut Room, world: &mut World<Dungeon>) {
+        self.ticks += 1;
+        if !self.ticks.is_multiple_of(BEAM_INTERVAL) {
+            return;
+        }
+        for (from, to) in self.beams.iter() {
+            let steps = (from.distance(*to) * 2.0).ceil() as usize;
+            for step in 0..=steps {
+                let position = from.lerp(*to, step as f64 / steps as f64);
+                world.spawn_particle_with_args(
+                    Particle::BlockDust,
+                    position.as_vec3(),
+                    Default::default(),
+                    1,
+                    vec![block_dust_argument(Block::SeaLantern)],
+                );
+            }
+        }
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        let is_lantern = |position: &IVec3| {
+            player.world().chunk_grid.get_block_at(position.x, position.y, position.z) == Block::SeaLantern
+                && self.lanterns.iter().flatten().any(|lantern| lantern == position)
+        };
+        if !is_lantern(&position) {
+            return;
+        }
+
+        let Some(selected) = self.selected.remove(&player.client_id) else {
+            self.selected.insert(player.client_id, position);
+            player.play_sound_at(Sound::NoteHat, 1.0, 1.5, block_center(position));
+            return;
+        };
+        if selected == position || !is_lantern(&selected) {
+            return;
+        }
+
+        let creeper = room.get_world_block_position(CREEPER_POSITION);
+        if !passes_through_creeper(creeper, selected, position) {
+            player.send_message("§cThose lanterns don't line up with the creeper!");
+            player.play_sound_at(Sound::NoteHarp, 1.0, 0.5, block_center(position));
+            return;
+        }
+
+        let world = player.world_mut();
+        for lantern in [selected, position] {
+            world.chunk_grid.set_block_at(Block::Prismarine, lantern.x, lantern.y, lantern.z);
+        }
+        self.beams.push((block_center(selected), block_center(position)));
+        player.play_sound_at(Sound::NoteHarp, 1.0, 1.0 + self.beams.len() as f32 * 0.2, block_center(position));
+
+        if self.beams.len() == self.lanterns.len() {
+            world.write_global_packet(&Chat::new(
+                &format!("§aPUZZLE SOLVED! §7{} §esolved the §aCreeper Beams§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+            ));
+            if let Some(creeper) = self.creeper.take() {
+                world.remove_entity(creeper);
+            }
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::SeededRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn only_pairs_line_up_with_the_creeper() {
+        for seed in 0..200 {
+            SeededRng::set_seed(seed);
+            let lanterns = generate_lanterns();
+            assert_eq!(lanterns.len(), PAIRS, "seed {seed}");
+
+            let positions = lanterns.iter().flatten().copied().collect::<HashSet<IVec3>>();
+            assert_eq!(positions.len(), PAIRS * 2, "seed {seed}");
+
+            for (index, [a, b]) in lanterns.iter().enumerate() {
+                assert!(passes_through_creeper(CREEPER_POSITION, *a, *b), "seed {seed}");
+                for [c, d] in lanterns[index + 1..].iter() {
+                    for (first, second) in [(a, c), (a, d), (b, c), (b, d)] {
+                        assert!(!passes_through_creeper(CREEPER_POSITION, *first, *second), "seed {seed}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_lanterns() {
+        SeededRng::set_seed(7);
+        let first = generate_lanterns();
+        SeededRng::set_seed(7);
+        assert_eq!(first, generate_lanterns());
+    }
+}
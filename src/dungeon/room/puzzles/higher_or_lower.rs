@@ -0,0 +1,186 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::dungeon_mob::{DungeonMob, DungeonMobAppearance, MobKind};
+use crate::dungeon::entities::health::Health;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, IVec3};
+use rand::seq::index::sample;
+use rand::Rng;
+use server::network::protocol::play::clientbound::Chat;
+use server::World;
+use std::f64::consts::TAU;
+
+const BLAZES: usize = 10;
+
+// blazes stand on a ring around the middle of the room
+const RING_CENTER: IVec3 = ivec3(15, 69, 15);
+const RING_RADIUS: f64 = 9.0;
+
+// health is picked from these in steps of 100, so no two nametags look the same
+const MIN_HEALTH: usize = 1_000;
+const MAX_HEALTH: usize = 30_000;
+
+#[derive(Debug, PartialEq)]
+pub enum KillResult {
+    Correct,
+    Wrong,
+    Finished,
+    // not one of the tracked entities
+    Ignored,
+}
+
+/// checks that things are killed in the order they were given in
+pub struct KillOrder<T> {
+    remaining: Vec<T>,
+}
+
+impl<T: PartialEq> KillOrder<T> {
+    pub fn new(order: Vec<T>) -> Self {
+        Self {
+            remaining: order,
+        }
+    }
+
+    pub fn on_kill(&mut self, killed: &T) -> KillResult {
+        match self.remaining.iter().position(|it| it == killed) {
+            None => KillResult::Ignored,
+            Some(0) => {
+                self.remaining.remove(0);
+                if self.remaining.is_empty() {
+                    KillResult::Finished
+                } else {
+                    KillResult::Correct
+                }
+            }
+            Some(_) => KillResult::Wrong,
+        }
+    }
+}
+
+pub struct HigherOrLowerPuzzle {
+    // health of each blaze, in the order they are placed around the ring
+    healths: Vec<f32>,
+    lowest_first: bool,
+    blazes: Vec<Entity>,
+    order: KillOrder<Entity>,
+}
+
+impl Default for HigherOrLowerPuzzle {
+    fn default() -> Self {
+        let mut rng = seeded_rng();
+        let healths = sample(&mut rng, (MAX_HEALTH - MIN_HEALTH) / 100, BLAZES)
+            .into_iter()
+            .map(|index| (MIN_HEALTH + index * 100) as f32)
+            .collect();
+        Self {
+            healths,
+            lowest_first: rng.random_bool(0.5),
+            blazes: Vec::new(),
+            order: KillOrder::new(Vec::new()),
+        }
+    }
+}
+
+impl RoomImplementation for HigherOrLowerPuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        let center = room.get_world_block_position(RING_CENTER).as_dvec3() + dvec3(0.5, 0.0, 0.5);
+
+        for (index, health) in self.healths.iter().enumerate() {
+            let angle = index as f64 / BLAZES as f64 * TAU;
+            let position = center + dvec3(angle.cos(), 0.0, angle.sin()) * RING_RADIUS;
+            let mob = DungeonMob {
+                kind: MobKind::Blaze,
+                starred: false,
+            };
+            let entity = world.spawn_entity(
+                position,
+                (angle.to_degrees() + 90.0) as f32,
+                0.0,
+                DungeonMobAppearance {
+                    variant: MobKind::Blaze.variant(),
+                    metadata: MobKind::Blaze.metadata(),
+                    name: mob.display_name(*health),
+                },
+                (mob, Health::new(*health)),
+            );
+            self.blazes.push(entity);
+        }
+
+        let mut order = self.blazes.iter().copied().zip(self.healths.iter().copied()).collect::<Vec<(Entity, f32)>>();
+        order.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        if !self.lowest_first {
+            order.reverse();
+        }
+        self.order = KillOrder::new(order.into_iter().map(|(entity, _)| entity).collect());
+
+        let message = if self.lowest_first {
+            "§eKill the blazes from §alowest §eto §chighest §ehealth!"
+        } else {
+            "§eKill the blazes from §chighest §eto §alowest §ehealth!"
+        };
+        for player in room.players() {
+            player.send_message(message);
+        }
+    }
+
+    fn on_entity_death(&mut self, room: &mut Room, world: &mut World<Dungeon>, entity: Entity) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        match self.order.on_kill(&entity) {
+            KillResult::Ignored | KillResult::Correct => return,
+            KillResult::Wrong => {
+                world.write_global_packet(&Chat::new("§cPUZZLE FAIL! §eA §6Blaze §ewas killed out of order! §4Y§ci§6k§ee§as§2!"));
+                room.status = RoomStatus::Failed;
+            }
+            KillResult::Finished => {
+                world.write_global_packet(&Chat::new("§aPUZZLE SOLVED! §eEvery §6Blaze §ewas killed in order! §4G§co§6o§ed §2j§bo§3b§5!"));
+                room.status = RoomStatus::Complete;
+            }
+        }
+        for blaze in self.blazes.drain(..) {
+            if blaze != entity && world.entities.contains(blaze) {
+                world.remove_entity(blaze);
+            }
+        }
+        world.map.draw_checkmark(room);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::SeededRng;
+
+    #[test]
+    fn kills_in_order() {
+        let mut order = KillOrder::new(vec![3, 1, 2]);
+        assert_eq!(order.on_kill(&5), KillResult::Ignored);
+        assert_eq!(order.on_kill(&3), KillResult::Correct);
+        assert_eq!(order.on_kill(&1), KillResult::Correct);
+        assert_eq!(order.on_kill(&2), KillResult::Finished);
+    }
+
+    #[test]
+    fn kill_out_of_order() {
+        let mut order = KillOrder::new(vec![3, 1, 2]);
+        assert_eq!(order.on_kill(&1), KillResult::Wrong);
+        // already killed ones don't count anymore
+        assert_eq!(order.on_kill(&3), KillResult::Correct);
+        assert_eq!(order.on_kill(&3), KillResult::Ignored);
+    }
+
+    #[test]
+    fn healths_are_unique() {
+        for seed in 0..50 {
+            SeededRng::set_seed(seed);
+            let mut healths = HigherOrLowerPuzzle::default().healths;
+            assert_eq!(healths.len(), BLAZES);
+            healths.sort_by(f32::total_cmp);
+            healths.dedup();
+            assert_eq!(healths.len(), BLAZES, "seed {seed}");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::config;
 use crate::dungeon::door::sound_emitter::DoorSoundEmitter;
 use crate::dungeon::dungeon::Dungeon;
 use crate::dungeon::dungeon_player::DungeonPlayer;
@@ -7,19 +8,211 @@ use crate::dungeon::entities::moving_block_behaviour::MovingBlockBehaviour;
 use crate::dungeon::room::room::{Room, RoomStatus};
 use crate::dungeon::room::room_implementation::RoomImplementation;
 use crate::dungeon::seeded_rng::seeded_rng;
-use glam::{dvec3, ivec3, IVec3};
+use anyhow::bail;
+use glam::{dvec3, ivec3, vec3, IVec3, Vec3};
 use rand::prelude::IndexedRandom;
+use serde::Deserialize;
 use server::block::Block;
-use server::constants::Sound;
+use server::constants::{Particle, Sound};
 use server::entity::components::entity_appearance::NoAppearance;
 use server::network::protocol::play::clientbound::Effect;
 use server::{ClientId, World};
 use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+const LAYER_DATA: &str = include_str!("../../../../data/puzzles/ice_fill.json");
+
+const HINT_INTERVAL: usize = 10;
 
 pub struct IceFillPuzzle {
     current_layer: usize,
     layer: Layer,
-    obstacles: [HashSet<IVec3>; 3]
+    // the variant picked for each layer
+    variants: Vec<&'static IceFillVariant>,
+    ticks: usize,
+}
+
+#[derive(Deserialize)]
+struct LayerData {
+    corner: [i32; 3],
+    size: i32,
+    // rows along z, # is an obstacle
+    variants: Vec<Vec<String>>,
+}
+
+/// a square of ice, entered from the middle of the first row,
+/// with an exit block past the middle of the last one
+pub struct IceFillLayer {
+    ice: Vec<IVec3>,
+    variants: Vec<IceFillVariant>,
+}
+
+pub struct IceFillVariant {
+    // one above the ice they block
+    obstacles: HashSet<IVec3>,
+    // every free ice block, in the order they have to be walked
+    solution: Vec<IVec3>,
+}
+
+/// loads every ice fill layer, and the variants that can be solved.
+/// variants that can't be are returned as errors
+pub fn load_ice_fill_layers() -> (Vec<IceFillLayer>, Vec<String>) {
+    let data: Vec<LayerData> = match serde_json::from_str::<serde_json::Value>(LAYER_DATA)
+        .and_then(|mut value| serde_json::from_value(value["layers"].take()))
+    {
+        Ok(data) => data,
+        Err(error) => return (Vec::new(), vec![format!("invalid ice fill data: {error}")]),
+    };
+
+    let mut layers = Vec::new();
+    let mut errors = Vec::new();
+
+    for (layer_index, layer) in data.into_iter().enumerate() {
+        let corner = IVec3::from_array(layer.corner);
+        let entrance = corner + ivec3(layer.size / 2, 0, 0);
+        let exit = corner + ivec3(layer.size / 2, 0, layer.size);
+
+        let mut ice = Vec::new();
+        for z in 0..layer.size {
+            for x in 0..layer.size {
+                ice.push(corner + ivec3(x, 0, z));
+            }
+        }
+        ice.push(exit);
+
+        let mut variants = Vec::new();
+        for (variant_index, rows) in layer.variants.iter().enumerate() {
+            let variant = parse_variant(rows, corner, layer.size).and_then(|obstacles| {
+                let free = ice.iter()
+                    .copied()
+                    .filter(|block| !obstacles.contains(&(block + IVec3::Y)))
+                    .collect::<HashSet<IVec3>>();
+                match solve(&free, entrance, exit) {
+                    Some(solution) => Ok(IceFillVariant { obstacles, solution }),
+                    None => Err("there is no path that goes over every block once".to_string()),
+                }
+            });
+            match variant {
+                Ok(variant) => variants.push(variant),
+                Err(error) => errors.push(format!("layer {layer_index} variant {variant_index}: {error}")),
+            }
+        }
+        if variants.is_empty() {
+            errors.push(format!("layer {layer_index} has no valid variants"));
+        }
+        layers.push(IceFillLayer { ice, variants });
+    }
+    (layers, errors)
+}
+
+fn parse_variant(rows: &[String], corner: IVec3, size: i32) -> Result<HashSet<IVec3>, String> {
+    if rows.len() != size as usize || rows.iter().any(|row| row.len() != size as usize) {
+        return Err(format!("should be {size} rows of {size} blocks"));
+    }
+    let mut obstacles = HashSet::new();
+    for (z, row) in rows.iter().enumerate() {
+        for (x, char) in row.chars().enumerate() {
+            match char {
+                '#' => {
+                    obstacles.insert(corner + ivec3(x as i32, 1, z as i32));
+                }
+                '.' => {}
+                _ => return Err(format!("unknown block {char}")),
+            }
+        }
+    }
+    Ok(obstacles)
+}
+
+/// finds a path from the entrance to the exit that goes over every block exactly once
+fn solve(free: &HashSet<IVec3>, entrance: IVec3, exit: IVec3) -> Option<Vec<IVec3>> {
+    if !free.contains(&entrance) || !free.contains(&exit) {
+        return None;
+    }
+    // steps alternate between checkerboard colors, so the counts have to line up
+    let is_black = |block: &IVec3| (block.x + block.z).rem_euclid(2) == 0;
+    let black = free.iter().filter(|block| is_black(block)).count();
+    let white = free.len() - black;
+    let even = free.len().is_multiple_of(2);
+    let expected = match (even, is_black(&entrance)) {
+        (true, _) => black == white,
+        (false, true) => black == white + 1,
+        (false, false) => white == black + 1,
+    };
+    if !expected || even == (is_black(&entrance) == is_black(&exit)) {
+        return None;
+    }
+
+    let mut path = vec![entrance];
+    let mut visited = HashSet::from([entrance]);
+    if extend_path(free, exit, &mut path, &mut visited) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+const NEIGHBOURS: [IVec3; 4] = [ivec3(1, 0, 0), ivec3(-1, 0, 0), ivec3(0, 0, 1), ivec3(0, 0, -1)];
+
+fn extend_path(free: &HashSet<IVec3>, exit: IVec3, path: &mut Vec<IVec3>, visited: &mut HashSet<IVec3>) -> bool {
+    let current = *path.last().unwrap();
+    if path.len() == free.len() {
+        return current == exit;
+    }
+    if current == exit || !is_connected(free, visited) {
+        return false;
+    }
+    for offset in NEIGHBOURS {
+        let next = current + offset;
+        if !free.contains(&next) || !visited.insert(next) {
+            continue;
+        }
+        path.push(next);
+        if extend_path(free, exit, path, visited) {
+            return true;
+        }
+        path.pop();
+        visited.remove(&next);
+    }
+    false
+}
+
+// if the blocks left over are split up, they can't all be walked
+fn is_connected(free: &HashSet<IVec3>, visited: &HashSet<IVec3>) -> bool {
+    let Some(start) = free.iter().find(|block| !visited.contains(block)).copied() else {
+        return true;
+    };
+    let mut stack = vec![start];
+    let mut reached = HashSet::from([start]);
+    while let Some(block) = stack.pop() {
+        for offset in NEIGHBOURS {
+            let next = block + offset;
+            if free.contains(&next) && !visited.contains(&next) && reached.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    reached.len() + visited.len() == free.len()
+}
+
+static ICE_FILL_LAYERS: LazyLock<Vec<IceFillLayer>> = LazyLock::new(|| {
+    let (layers, errors) = load_ice_fill_layers();
+    for error in errors.iter() {
+        eprintln!("warning: skipping ice fill {error}");
+    }
+    layers
+});
+
+/// loads the layers up front, so bad data stops the server on startup
+/// instead of when the first ice fill room is created
+pub fn init_ice_fill_layers() -> anyhow::Result<()> {
+    if ICE_FILL_LAYERS.is_empty() {
+        bail!("there are no ice fill layers");
+    }
+    if let Some(index) = ICE_FILL_LAYERS.iter().position(|layer| layer.variants.is_empty()) {
+        bail!("ice fill layer {index} has no valid variants");
+    }
+    Ok(())
 }
 
 pub enum Layer {
@@ -38,14 +231,15 @@ pub enum Layer {
 impl Default for IceFillPuzzle {
     fn default() -> Self {
         let mut rng = seeded_rng();
-        let obstacles = OBSTACLES.each_ref().map(|choices| {
-            let choice = choices.choose(&mut rng).copied().unwrap_or(&[]);
-            choice.iter().copied().collect::<HashSet<_>>()
-        });
+        let variants = ICE_FILL_LAYERS.iter()
+            // checked on startup by init_ice_fill_layers
+            .map(|layer| layer.variants.choose(&mut rng).expect("ice fill layer should have a valid variant"))
+            .collect();
         Self {
             current_layer: 0,
             layer: Layer::Inactive,
-            obstacles,
+            variants,
+            ticks: 0,
         }
     }
 }
@@ -61,9 +255,21 @@ impl RoomImplementation for IceFillPuzzle {
         if !matches!(room.status, RoomStatus::Discovered) {
             return;
         }
+        self.ticks += 1;
         match &mut self.layer {
             Layer::Active { ice, obstacles, remaining_ice_blocks, tracked_positions } => {
 
+                // practice mode, shows which blocks are left in the order they should be walked
+                if config().ice_fill_hints && self.ticks.is_multiple_of(HINT_INTERVAL) {
+                    for block in self.variants[self.current_layer].solution.iter() {
+                        let position = room.get_world_block_position(*block);
+                        if remaining_ice_blocks.contains(&position) {
+                            let particle = position.as_vec3() + vec3(0.5, 1.1, 0.5);
+                            world.spawn_particle(Particle::VillagerHappy, particle, Vec3::ZERO, 1);
+                        }
+                    }
+                }
+
                 let mut result: Option<bool> = None;
 
                 for player in room.players() {
@@ -123,7 +329,7 @@ impl RoomImplementation for IceFillPuzzle {
                         }
                         self.current_layer += 1;
                         // no more layers
-                        if self.current_layer == 3 {
+                        if self.current_layer == self.variants.len() {
                             self.complete(room, world)
                         } else {
                             self.spawn_active_layer(room, world);
@@ -217,8 +423,9 @@ impl IceFillPuzzle {
                     },
                     MovingBlockBehaviour {
                         block,
-                        remove_block_in_tick: 20,
-                        difference: 0.2,
+                        replace_with: Block::Air,
+                        replace_in_tick: 20,
+                        difference: dvec3(0.0, 0.2, 0.0),
                     }
                 );
             }
@@ -226,8 +433,8 @@ impl IceFillPuzzle {
     }
 
     pub fn spawn_active_layer(&mut self, room: &Room, world: &mut World<Dungeon>) {
-        let ice = LAYERS[self.current_layer];
-        let obstacles = &self.obstacles[self.current_layer];
+        let ice = &ICE_FILL_LAYERS[self.current_layer].ice;
+        let obstacles = &self.variants[self.current_layer].obstacles;
 
         for position in ice.iter() {
             let IVec3 { x, y, z } = room.get_world_block_position(*position);
@@ -263,61 +470,40 @@ impl IceFillPuzzle {
     }
 }
 
-// ice blocks
-const LAYERS: [&[IVec3]; 3] = [
-    &[
-        ivec3(14, 69, 7), ivec3(15, 69, 7), ivec3(15, 69, 8),
-        ivec3(14, 69, 8), ivec3(15, 69, 9), ivec3(16, 69, 7),
-        ivec3(14, 69, 9), ivec3(16, 69, 8), ivec3(16, 69, 9),
-        ivec3(15, 69, 10),
-    ],
-    &[
-        ivec3(13, 70, 12), ivec3(14, 70, 12), ivec3(15, 70, 12), ivec3(16, 70, 12), ivec3(17, 70, 12),
-        ivec3(13, 70, 13), ivec3(14, 70, 13), ivec3(15, 70, 13), ivec3(16, 70, 13), ivec3(17, 70, 13),
-        ivec3(13, 70, 14), ivec3(14, 70, 14), ivec3(15, 70, 14), ivec3(16, 70, 14), ivec3(17, 70, 14),
-        ivec3(13, 70, 15), ivec3(14, 70, 15), ivec3(15, 70, 15), ivec3(16, 70, 15), ivec3(17, 70, 15),
-        ivec3(13, 70, 16), ivec3(14, 70, 16), ivec3(15, 70, 16), ivec3(16, 70, 16), ivec3(17, 70, 16),
-        ivec3(15, 70, 17),
-    ],
-    &[
-        ivec3(12, 71, 19), ivec3(13, 71, 19), ivec3(14, 71, 19), ivec3(15, 71, 19), ivec3(16, 71, 19), ivec3(17, 71, 19), ivec3(18, 71, 19),
-        ivec3(12, 71, 20), ivec3(13, 71, 20), ivec3(14, 71, 20), ivec3(15, 71, 20), ivec3(16, 71, 20), ivec3(17, 71, 20), ivec3(18, 71, 20),
-        ivec3(12, 71, 21), ivec3(13, 71, 21), ivec3(14, 71, 21), ivec3(15, 71, 21), ivec3(16, 71, 21), ivec3(17, 71, 21), ivec3(18, 71, 21),
-        ivec3(12, 71, 22), ivec3(13, 71, 22), ivec3(14, 71, 22), ivec3(15, 71, 22), ivec3(16, 71, 22), ivec3(17, 71, 22), ivec3(18, 71, 22),
-        ivec3(12, 71, 23), ivec3(13, 71, 23), ivec3(14, 71, 23), ivec3(15, 71, 23), ivec3(16, 71, 23), ivec3(17, 71, 23), ivec3(18, 71, 23),
-        ivec3(12, 71, 24), ivec3(13, 71, 24), ivec3(14, 71, 24), ivec3(15, 71, 24), ivec3(16, 71, 24), ivec3(17, 71, 24), ivec3(18, 71, 24),
-        ivec3(12, 71, 25), ivec3(13, 71, 25), ivec3(14, 71, 25), ivec3(15, 71, 25), ivec3(16, 71, 25), ivec3(17, 71, 25), ivec3(18, 71, 25),
-        ivec3(15, 71, 26),
-    ],
-];
-
-const OBSTACLES: [&[&[IVec3]]; 3] = [
-    &[
-        &[
-            ivec3(14, 70, 7),
-            ivec3(16, 70, 9),
-        ],
-    ],
-    &[
-        &[
-            ivec3(15, 71, 13),
-            ivec3(17, 71, 12),
-            ivec3(17, 71, 16),
-            ivec3(17, 71, 15),
-            ivec3(16, 71, 16),
-            ivec3(14, 71, 15),
-        ]
-    ],
-    &[
-        &[
-            ivec3(14, 72, 19),
-            ivec3(14, 72, 20),
-            ivec3(16, 72, 21),
-            ivec3(16, 72, 22),
-            ivec3(17, 72, 22),
-            ivec3(16, 72, 25),
-            ivec3(13, 72, 24),
-            ivec3(14, 72, 24),
-        ]
-    ],
-];
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_is_solvable() {
+        let (layers, errors) = load_ice_fill_layers();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(layers.len(), 3);
+
+        for layer in layers.iter() {
+            for variant in layer.variants.iter() {
+                let free = layer.ice.iter().filter(|block| !variant.obstacles.contains(&(**block + IVec3::Y))).count();
+                assert_eq!(variant.solution.len(), free);
+                assert_eq!(variant.solution.iter().collect::<HashSet<_>>().len(), free);
+                for step in variant.solution.windows(2) {
+                    assert_eq!((step[1] - step[0]).abs().element_sum(), 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsolvable_layouts_are_rejected() {
+        let corner = ivec3(0, 0, 0);
+        let mut ice = (0..3).flat_map(|z| (0..3).map(move |x| ivec3(x, 0, z))).collect::<HashSet<IVec3>>();
+        ice.insert(ivec3(1, 0, 3));
+        assert!(solve(&ice, ivec3(1, 0, 0), ivec3(1, 0, 3)).is_none());
+
+        let rows = ["#..", "...", "..#"].map(String::from);
+        let obstacles = parse_variant(&rows, corner, 3).unwrap();
+        ice.retain(|block| !obstacles.contains(&(block + IVec3::Y)));
+        assert!(solve(&ice, ivec3(1, 0, 0), ivec3(1, 0, 3)).is_some());
+
+        assert!(parse_variant(&["#..", ".."].map(String::from), corner, 3).is_err());
+    }
+}
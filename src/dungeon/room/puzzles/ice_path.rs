@@ -0,0 +1,254 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use bevy_ecs::prelude::Component;
+use glam::{dvec3, ivec2, ivec3, DVec3, IVec2, IVec3};
+use rand::Rng;
+use server::block::Block;
+use server::constants::{EntityVariant, Sound};
+use server::entity::components::{EntityBehaviour, MobAppearance};
+use server::entity::entity::MinecraftEntity;
+use server::entity::entity_metadata::EntityMetadata;
+use server::network::protocol::play::clientbound::Chat;
+use server::{Player, World};
+
+// 11 by 11 layouts, # is an obstacle, S is where the silverfish starts and E is the exit
+const LAYOUTS: &str = include_str!("../../../../data/puzzles/ice_path.txt");
+
+const SIZE: i32 = 11;
+// floor of the cell in the first column and row, columns go along x and rows along z
+const LAYOUT_ORIGIN: IVec3 = ivec3(10, 68, 10);
+
+// blocks per tick
+const SLIDE_SPEED: f64 = 0.5;
+
+const DIRECTIONS: [IVec2; 4] = [ivec2(0, -1), ivec2(1, 0), ivec2(0, 1), ivec2(-1, 0)];
+
+struct Layout {
+    obstacles: Vec<IVec2>,
+    start: IVec2,
+    exit: IVec2,
+}
+
+impl Layout {
+    fn is_open(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.x < SIZE && cell.y >= 0 && cell.y < SIZE && !self.obstacles.contains(&cell)
+    }
+
+    /// where the silverfish stops when pushed from this cell
+    fn slide(&self, from: IVec2, direction: IVec2) -> IVec2 {
+        let mut cell = from;
+        while self.is_open(cell + direction) {
+            cell += direction;
+        }
+        cell
+    }
+}
+
+fn parse_layouts() -> Vec<Layout> {
+    LAYOUTS.split("\n\n")
+        .map(|layout| {
+            let mut obstacles = Vec::new();
+            let mut start = None;
+            let mut exit = None;
+            for (row, line) in layout.lines().enumerate() {
+                for (column, char) in line.chars().enumerate() {
+                    let cell = ivec2(column as i32, row as i32);
+                    match char {
+                        '#' => obstacles.push(cell),
+                        'S' => start = Some(cell),
+                        'E' => exit = Some(cell),
+                        _ => {}
+                    }
+                }
+            }
+            Layout {
+                obstacles,
+                start: start.expect("ice path layout should have a start"),
+                exit: exit.expect("ice path layout should have an exit"),
+            }
+        })
+        .collect()
+}
+
+fn cell_position(cell: IVec2) -> IVec3 {
+    LAYOUT_ORIGIN + ivec3(cell.x, 0, cell.y)
+}
+
+/// moves the entity in a straight line to the target, a bit every tick
+#[derive(Component)]
+pub struct SlidingBehaviour {
+    pub target: DVec3,
+}
+
+impl EntityBehaviour<Dungeon> for SlidingBehaviour {
+    fn tick(entity: &mut MinecraftEntity<Dungeon>, component: &mut Self) {
+        let offset = component.target - entity.position;
+        if offset.length() <= SLIDE_SPEED {
+            entity.position = component.target;
+        } else {
+            entity.position += offset.normalize() * SLIDE_SPEED;
+        }
+    }
+}
+
+pub struct IcePathPuzzle {
+    layout: Layout,
+    cell: IVec2,
+    silverfish: Option<Entity>,
+}
+
+impl Default for IcePathPuzzle {
+    fn default() -> Self {
+        let mut layouts = parse_layouts();
+        let layout = layouts.swap_remove(seeded_rng().random_range(0..layouts.len()));
+        Self {
+            cell: layout.start,
+            layout,
+            silverfish: None,
+        }
+    }
+}
+
+impl IcePathPuzzle {
+    fn silverfish_position(&self, room: &Room, cell: IVec2) -> DVec3 {
+        room.get_world_block_position(cell_position(cell) + IVec3::Y).as_dvec3() + dvec3(0.5, 0.0, 0.5)
+    }
+
+    fn is_sliding(&self, world: &World<Dungeon>) -> bool {
+        let Some(silverfish) = self.silverfish else {
+            return false;
+        };
+        let entity = world.entities.get_entity(silverfish);
+        match (entity.get::<MinecraftEntity<Dungeon>>(), entity.get::<SlidingBehaviour>()) {
+            (Some(mc_entity), Some(sliding)) => mc_entity.position != sliding.target,
+            _ => false,
+        }
+    }
+}
+
+impl RoomImplementation for IcePathPuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for column in 0..SIZE {
+            for row in 0..SIZE {
+                let cell = ivec2(column, row);
+                let floor = if cell == self.layout.exit { Block::EmeraldBlock } else { Block::PackedIce };
+                let IVec3 { x, y, z } = room.get_world_block_position(cell_position(cell));
+                world.chunk_grid.set_block_at(floor, x, y, z);
+            }
+        }
+        for obstacle in self.layout.obstacles.iter() {
+            let IVec3 { x, y, z } = room.get_world_block_position(cell_position(*obstacle) + IVec3::Y);
+            world.chunk_grid.set_block_at(Block::StoneBricks, x, y, z);
+        }
+
+        let position = self.silverfish_position(room, self.cell);
+        self.silverfish = Some(world.spawn_entity(
+            position,
+            0.0,
+            0.0,
+            MobAppearance {
+                variant: EntityVariant::Silverfish,
+                metadata: EntityMetadata::Silverfish(Default::default()),
+            },
+            SlidingBehaviour {
+                target: position,
+            },
+        ));
+    }
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        if !matches!(room.status, RoomStatus::Discovered) || self.cell != self.layout.exit || self.is_sliding(world) {
+            return;
+        }
+        world.write_global_packet(&Chat::new("§aPUZZLE SOLVED! §eThe §7Silverfish §emade it across the ice! §4G§co§6o§ed §2j§bo§3b§5!"));
+        if let Some(silverfish) = self.silverfish.take() {
+            world.remove_entity(silverfish);
+        }
+        room.status = RoomStatus::Complete;
+        world.map.draw_checkmark(room);
+    }
+
+    fn attack(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, entity: Entity) {
+        if !matches!(room.status, RoomStatus::Discovered) || self.silverfish != Some(entity) || self.is_sliding(player.world()) {
+            return;
+        }
+
+        // push it the way the player is facing, snapped to the closest side
+        let yaw = (player.yaw as f64).to_radians();
+        let facing = dvec3(-yaw.sin(), 0.0, yaw.cos());
+        let facing = if facing.x.abs() > facing.z.abs() {
+            ivec3(facing.x.signum() as i32, 0, 0)
+        } else {
+            ivec3(0, 0, facing.z.signum() as i32)
+        };
+        // the room could be rotated, so find which way that is in the layout
+        let origin = room.get_world_block_position(LAYOUT_ORIGIN);
+        let Some(direction) = DIRECTIONS.into_iter().find(|direction| {
+            room.get_world_block_position(LAYOUT_ORIGIN + ivec3(direction.x, 0, direction.y)) - origin == facing
+        }) else {
+            return;
+        };
+
+        let next = self.layout.slide(self.cell, direction);
+        if next == self.cell {
+            return;
+        }
+        self.cell = next;
+        let target = self.silverfish_position(room, next);
+
+        let world = player.world_mut();
+        if let Some(mut sliding) = world.entities.get_entity_mut(entity).get_mut::<SlidingBehaviour>() {
+            sliding.target = target;
+        }
+        player.play_sound_at(Sound::RandomWoodClick, 1.0, 1.5, target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, VecDeque};
+
+    // fewest pushes to get from the start to the exit
+    fn solve(layout: &Layout) -> Option<usize> {
+        let mut pushes = HashMap::from([(layout.start, 0)]);
+        let mut queue = VecDeque::from([layout.start]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == layout.exit {
+                return pushes.get(&cell).copied();
+            }
+            for direction in DIRECTIONS {
+                let next = layout.slide(cell, direction);
+                if !pushes.contains_key(&next) {
+                    pushes.insert(next, pushes[&cell] + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn every_layout_is_solvable() {
+        let layouts = parse_layouts();
+        assert!(!layouts.is_empty());
+        for (index, layout) in layouts.iter().enumerate() {
+            assert!(solve(layout).is_some_and(|pushes| pushes > 1), "layout {index}");
+        }
+    }
+
+    #[test]
+    fn slides_until_blocked() {
+        let layout = Layout {
+            obstacles: vec![ivec2(5, 2)],
+            start: ivec2(5, 10),
+            exit: ivec2(0, 0),
+        };
+        assert_eq!(layout.slide(ivec2(5, 10), ivec2(0, -1)), ivec2(5, 3));
+        assert_eq!(layout.slide(ivec2(5, 10), ivec2(1, 0)), ivec2(10, 10));
+    }
+}
@@ -1,4 +1,11 @@
 pub mod three_weirdos;
 pub mod quiz;
 pub mod teleport_maze;
-pub mod ice_fill;
\ No newline at end of file
+pub mod ice_fill;
+pub mod creeper_beams;
+pub mod water_board;
+pub mod tic_tac_toe;
+pub mod boulder;
+pub mod higher_or_lower;
+pub mod ice_path;
+pub mod bomb_defuse;
\ No newline at end of file
@@ -1,25 +1,248 @@
 use crate::dungeon::dungeon::Dungeon;
 use crate::dungeon::dungeon_player::DungeonPlayer;
-use crate::dungeon::room::room::Room;
+use crate::dungeon::entities::hologram::HologramAppearance;
+use crate::dungeon::entities::npc::NPCBehaviour;
+use crate::dungeon::room::room::{Room, RoomStatus};
 use crate::dungeon::room::room_implementation::RoomImplementation;
-use glam::IVec3;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, IVec3};
+use rand::prelude::{IndexedRandom, SliceRandom};
+use serde::Deserialize;
+use server::block::rotatable::Rotate;
+use server::constants::Sound;
+use server::entity::components::entity_appearance::PlayerAppearance;
+use server::network::protocol::play::clientbound::Chat;
 use server::{Player, World};
 
-pub struct QuizPuzzle;
+const QUESTIONS: &str = include_str!("../../../../data/puzzles/quiz.json");
+
+const ORACLE_POSITION: IVec3 = ivec3(15, 69, 10);
+
+// buttons for ⓐ, ⓑ and ⓒ, the answers float above them
+const ANSWER_BUTTONS: [IVec3; 3] = [
+    ivec3(20, 70, 17),
+    ivec3(15, 70, 17),
+    ivec3(10, 70, 17),
+];
+
+const ANSWER_LABELS: [&str; 3] = ["ⓐ", "ⓑ", "ⓒ"];
+
+const ROUNDS: usize = 3;
+
+// ticks between the oracle talking and asking the next question
+const QUESTION_DELAY: usize = 60;
+
+const ORACLE_SKIN: (&str, &str) = (
+    "eyJ0aW1lc3RhbXAiOjE1ODIwNTY1MjY1ODksInByb2ZpbGVJZCI6ImEyZjgzNDU5NWM4OTRhMjdhZGQzMDQ5NzE2Y2E5MTBjIiwicHJvZmlsZU5hbWUiOiJiUHVuY2giLCJzaWduYXR1cmVSZXF1aXJlZCI6dHJ1ZSwidGV4dHVyZXMiOnsiU0tJTiI6eyJ1cmwiOiJodHRwOi8vdGV4dHVyZXMubWluZWNyYWZ0Lm5ldC90ZXh0dXJlLzYxOTBhN2IyYmIxM2FlNTgyM2Y2YTE4NDZmODQyYzM0ODllMjYyMGEzNjY1NTc0YTBmYmE5NzVjMzk0MTA5MjIifX19",
+    "D/AAFPkwp3dqEx8OktDtqX0PSwJfu6PS+u67e8mq+0FMz+yqvDhD4FmzlvJlz6dwVa+UWGBCX6CMPXbPja9eeR90GFEYU+AYInam8IvyrmDzw7q0Fx3jzP9aRmHSn4229Y8GXhkOJ37k3pWf5zrcIJmT9npIq4lwEc3B0OxEZtQadanWX0/qIr/bpbrB+en2zIWzzwQWIAXPJUwQgiVj7mRwfMCyajoOqGs0AApzTi5IPresYF2BZZ9pLWyLv96YFhm96ncMHVJlSl3h8mt0R1pGi2BwOROYIfFq6HDONpSfD3R7aaty0fyPeV9kcrswndCS5/ubZxvv1bLp2wqhR0A5NzWr2GM3GK7o2EQgM5o9gsKS65SGPaWF0h3dUzsrpCOSMKzxzj29eAP4TLsLxWNAyaR/Q4NQt8cluiisLSk2yKUDovzUiSqrdLToD+5DPFqNYxDRraCc2gQQlsHpp3aXHNpqoBYcczTXwUHgjqh71HodzXGo4pxOcJNo5kOjV+uyfgvR9zCIuoN3j7UK6E2F3LQrLDTTRb8W4KGkMDnEESv8jGrXwQs4WzqUdP4HHXOFHbp2cx0Pi7xc93MQW1ZmjNtDeLr+xvsqDbL2syI8D2mf2++vjLhPSzxqtx4hOcyWQFCMC9Sdzb/bDg8JAuXgQSAMgmp1E1Oz/msZJ1w=",
+);
+
+#[derive(Deserialize)]
+struct Question {
+    question: String,
+    correct: String,
+    wrong: [String; 2],
+}
+
+struct Round {
+    question: String,
+    answers: [String; 3],
+    correct_index: usize,
+}
+
+enum QuizState {
+    Waiting {
+        in_ticks: usize,
+    },
+    Answering {
+        holograms: Vec<Entity>,
+    },
+    Finished,
+}
+
+pub struct QuizPuzzle {
+    rounds: Vec<Round>,
+    current_round: usize,
+    state: QuizState,
+}
+
+fn questions() -> Vec<Question> {
+    serde_json::from_str(QUESTIONS).expect("quiz questions should be valid")
+}
+
+impl Default for QuizPuzzle {
+    fn default() -> Self {
+        let mut rng = seeded_rng();
+        let questions = questions();
+
+        let rounds = questions
+            .choose_multiple(&mut rng, ROUNDS)
+            .map(|question| {
+                let mut answers = [
+                    question.correct.clone(),
+                    question.wrong[0].clone(),
+                    question.wrong[1].clone(),
+                ];
+                answers.shuffle(&mut rng);
+                let correct_index = answers.iter().position(|answer| *answer == question.correct).unwrap();
+
+                Round {
+                    question: question.question.clone(),
+                    answers,
+                    correct_index,
+                }
+            })
+            .collect();
+
+        Self {
+            rounds,
+            current_round: 0,
+            state: QuizState::Waiting { in_ticks: QUESTION_DELAY },
+        }
+    }
+}
 
 impl RoomImplementation for QuizPuzzle {
-    fn discover(&mut self, room: &mut Room, _: &mut World<Dungeon>) {
-        // for player in room.players() {
-        //     player.write_packet(&Chat {
-        //         component: "quiz opened msg or something",
-        //         chat_type: 0,
-        //     })
-        // }
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        let mut position = room.get_world_block_position(ORACLE_POSITION).as_dvec3();
+        position.x += 0.5;
+        position.z += 0.5;
+        let yaw = 0.0.rotate(room.rotation);
+
+        let (texture, signature) = ORACLE_SKIN;
+        world.spawn_entity(
+            position,
+            yaw,
+            0.0,
+            PlayerAppearance::new("§5Oracle", Default::default(), texture, signature),
+            NPCBehaviour {
+                default_yaw: yaw,
+                default_pitch: 0.0,
+            },
+        );
+
+        for player in room.players() {
+            player.send_message("§e[STATUE] §5Oracle§f: This is a test of your knowledge. Answer three of my questions correctly to pass!");
+        }
     }
-    fn interact(&mut self, _: &mut Room, player: &mut Player<DungeonPlayer>, _: IVec3) {
-        // player.write_packet(&Chat {
-        //     component: "right clicked block in quiz",
-        //     chat_type: 0,
-        // })
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        if let QuizState::Waiting { in_ticks } = &mut self.state {
+            *in_ticks = in_ticks.saturating_sub(1);
+            if *in_ticks == 0 {
+                self.ask_question(room, world);
+            }
+        }
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        let QuizState::Answering { holograms } = &mut self.state else {
+            return;
+        };
+        let Some(index) = ANSWER_BUTTONS.iter().position(|button| room.get_world_block_position(*button) == position) else {
+            return;
+        };
+
+        let world = player.world_mut();
+        for entity in holograms.drain(..) {
+            world.remove_entity(entity);
+        }
+
+        let round = &self.rounds[self.current_round];
+        if index != round.correct_index {
+            world.write_global_packet(&Chat::new(
+                &format!("§cPUZZLE FAIL! §7{} §echose the wrong answer! The correct answer was §a{}§e!", player.profile.username, round.answers[round.correct_index]),
+            ));
+            player.play_sound_at(Sound::RandomExplode, 1.0, 1.0, position.as_dvec3());
+
+            self.state = QuizState::Finished;
+            room.status = RoomStatus::Failed;
+            world.map.draw_checkmark(room);
+            return;
+        }
+
+        world.write_global_packet(&Chat::new(
+            &format!("§e[STATUE] §5Oracle§f: §7{} §fanswered Question #{} correctly!", player.profile.username, self.current_round + 1),
+        ));
+        for player in room.players() {
+            DungeonPlayer::queue_sound(player, Sound::NoteHarp, 1.0, 1.5, 0);
+            DungeonPlayer::queue_sound(player, Sound::NoteHarp, 1.0, 2.0, 3);
+        }
+
+        self.current_round += 1;
+        if self.current_round == ROUNDS {
+            world.write_global_packet(&Chat::new(
+                &format!("§aPUZZLE SOLVED! §7{} §ewasn't fooled by the §5Oracle§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+            ));
+            self.state = QuizState::Finished;
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        } else {
+            self.state = QuizState::Waiting { in_ticks: QUESTION_DELAY };
+        }
+    }
+}
+
+impl QuizPuzzle {
+    fn ask_question(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        let round = &self.rounds[self.current_round];
+
+        for player in room.players() {
+            player.send_message(&format!("§e[STATUE] §5Oracle§f: Question #{}: §a{}", self.current_round + 1, round.question));
+            for (label, answer) in ANSWER_LABELS.iter().zip(round.answers.iter()) {
+                player.send_message(&format!("     §6{label} §a{answer}"));
+            }
+            player.play_sound(Sound::NoteHarp, 1.0, 1.0);
+        }
+
+        let holograms = ANSWER_BUTTONS.iter()
+            .zip(ANSWER_LABELS.iter().zip(round.answers.iter()))
+            .map(|(button, (label, answer))| {
+                let position = room.get_world_block_position(*button).as_dvec3() + dvec3(0.5, 1.0, 0.5);
+                world.spawn_entity(
+                    position,
+                    0.0,
+                    0.0,
+                    HologramAppearance {
+                        text: format!("§6{label} §a{answer}"),
+                    },
+                    (),
+                )
+            })
+            .collect();
+
+        self.state = QuizState::Answering { holograms };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn question_bank_is_valid() {
+        let questions = questions();
+        assert!(questions.len() >= ROUNDS);
+        for question in questions.iter() {
+            assert!(!question.wrong.contains(&question.correct), "{}", question.question);
+        }
+    }
+
+    #[test]
+    fn rounds_have_the_correct_answer() {
+        let quiz = QuizPuzzle::default();
+        assert_eq!(quiz.rounds.len(), ROUNDS);
+        let questions = questions();
+        for round in quiz.rounds.iter() {
+            let question = questions.iter().find(|question| question.question == round.question).unwrap();
+            assert_eq!(round.answers[round.correct_index], question.correct);
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,23 @@
-use crate::dungeon::dungeon::{Dungeon, DungeonState};
-use crate::dungeon::room::room::Room;
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::room::{Room, RoomStatus};
 use crate::dungeon::room::room_implementation::RoomImplementation;
 use crate::dungeon::seeded_rng::{seeded_rng, SeededRng};
 use glam::{dvec3, ivec3, IVec3};
 use rand::prelude::SliceRandom;
 use rand::Rng;
 use server::block::rotatable::Rotate;
-use server::network::protocol::play::clientbound::PositionLook;
+use server::constants::Sound;
+use server::network::protocol::play::clientbound::{BlockAction, Chat, PositionLook};
 use server::types::aabb::AABB;
-use server::World;
+use server::{ClientId, Player, World};
+use std::collections::HashMap;
 
 pub struct TeleportMazePuzzle {
     teleport_pads: Vec<TeleportPad>,
     target: IVec3,
+    // pad each player was on last tick, so standing on one only teleports once
+    standing_on: HashMap<ClientId, usize>,
 }
 
 struct TeleportPad {
@@ -33,40 +38,42 @@ impl TeleportPad {
     }
 }
 
+// corner of each 7x7 section, which has a pad in every corner
+const SECTION_OFFSETS: [IVec3; 7] = [
+    ivec3(20, 70, 6),
+    ivec3(20, 70, 14),
+    ivec3(20, 70, 22),
+    ivec3(12, 70, 22),
+    ivec3(4, 70, 22),
+    ivec3(4, 70, 14),
+    ivec3(4, 70, 6),
+];
+
+const ENTRANCE_PAD: IVec3 = ivec3(15, 70, 12);
+const EXIT_PAD: IVec3 = ivec3(15, 70, 14);
+const ENTRANCE_PAD_TO: IVec3 = ivec3(15, 69, 11);
+const EXIT_PAD_TO: IVec3 = ivec3(15, 69, 15);
+
+const CHEST_POSITION: IVec3 = ivec3(15, 70, 17);
+
 impl Default for TeleportMazePuzzle {
     fn default() -> Self {
         let mut teleport_pads: Vec<TeleportPad> = Vec::new();
 
         // not the prettiest
         let rng = &mut seeded_rng();
-        let mut sections: Vec<Vec<(IVec3, IVec3)>> = Vec::new();
-
-        for index in 0..7 {
-            let offset = match index {
-                0 => ivec3(20, 70, 6),
-                1 => ivec3(20, 70, 14),
-                2 => ivec3(20, 70, 22),
-                3 => ivec3(12, 70, 22),
-                4 => ivec3(4, 70, 22),
-                5 => ivec3(4, 70, 14),
-                6 => ivec3(4, 70, 6),
-                _ => unreachable!()
-            };
-            sections.push(vec![
+        let mut sections: Vec<Vec<(IVec3, IVec3)>> = SECTION_OFFSETS
+            .iter()
+            .map(|&offset| vec![
                 (offset, offset + ivec3(1, -1, 1)),
                 (offset + ivec3(6, 0, 0), offset + ivec3(5, -1, 1)),
                 (offset + ivec3(0, 0, 6), offset + ivec3(1, -1, 5)),
                 (offset + ivec3(6, 0, 6), offset + ivec3(5, -1, 5)),
-            ]);
-        }
+            ])
+            .collect();
 
         sections.shuffle(rng);
 
-        const ENTRANCE_PAD: IVec3 = ivec3(15, 70, 12);
-        const EXIT_PAD: IVec3 = ivec3(15, 70, 14);
-        const ENTRANCE_PAD_TO: IVec3 = ivec3(15, 69, 11);
-        const EXIT_PAD_TO: IVec3 = ivec3(15, 69, 15);
-
         // entrance
         let pads = &mut sections[0];
         let (pad, to_pad) = pop_with_rng(pads, rng);
@@ -116,30 +123,19 @@ impl Default for TeleportMazePuzzle {
             });
         }
 
-        let mut remaining: Vec<(usize, IVec3, IVec3)> = sections
-            .iter()
-            .enumerate()
-            .flat_map(|(section_idx, pads)| {
-                pads.iter().map(move |&(pad, to_pad)| (section_idx, pad, to_pad))
-            })
-            .collect();
-
-        remaining.shuffle(rng);
-
-        // this broke bru
-        let mut index = 0;
-        while index + 1 < remaining.len() {
-            if remaining[index].0 == remaining[index + 1].0 {
-                let j = remaining[index + 2..]
-                    .iter()
-                    .position(|(s, _, _)| *s != remaining[index].0)
-                    .expect("no valid cross-section partner found");
-
-                remaining.swap(index + 1, index + 2 + j);
-            }
+        // every section has 2 pads left, kept next to each other in this list.
+        // pairing each pad with the one half the list away can never pair a section with itself
+        sections.shuffle(rng);
+        let mut remaining: Vec<(IVec3, IVec3)> = Vec::new();
+        for pads in sections.iter_mut() {
+            pads.shuffle(rng);
+            remaining.append(pads);
+        }
 
-            let (_, pad_a, to_pad_a) = remaining[index];
-            let (_, pad_b, to_pad_b) = remaining[index + 1];
+        let half = remaining.len() / 2;
+        for index in 0..half {
+            let (pad_a, to_pad_a) = remaining[index];
+            let (pad_b, to_pad_b) = remaining[index + half];
             teleport_pads.push(TeleportPad {
                 position: pad_a,
                 teleports_to: to_pad_b,
@@ -150,13 +146,12 @@ impl Default for TeleportMazePuzzle {
                 teleports_to: to_pad_a,
                 rotate_type: TeleportPadRotation::TowardsEnd,
             });
-
-            index += 2;
         }
 
         Self {
             teleport_pads,
             target: pad_leading_to_end,
+            standing_on: HashMap::new(),
         }
     }
 }
@@ -171,49 +166,65 @@ impl RoomImplementation for TeleportMazePuzzle {
         self.target = room.get_world_block_position(self.target);
     }
 
-    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
-        // todo, improve accuracy
-        if let DungeonState::Started { ticks } = world.state && ticks % 10 != 0 {
-            return;
-        }
-        'outer: for player in room.players() {
+    fn tick(&mut self, room: &mut Room, _world: &mut World<Dungeon>) {
+        for player in room.players() {
             let player_aabb = player.collision_aabb();
-            for pad in self.teleport_pads.iter() {
-                if !pad.aabb().intersects(&player_aabb) {
-                    continue
-                }
-
-                let mut origin = pad.teleports_to.as_dvec3();
-                origin.x += 0.5;
-                origin.y += 0.5;
-                origin.z += 0.5;
-
-                let yaw = match pad.rotate_type {
-                    TeleportPadRotation::Static { yaw } => {
-                        yaw.rotate(room.rotation)
-                    },
-                    TeleportPadRotation::TowardsEnd => {
-                        let mut target = self.target.as_dvec3();
-                        target.x += 0.5;
-                        target.z += 0.5;
-                        let diff = target - origin;
-                        f64::atan2(-diff.x, diff.z).to_degrees() as f32
-                    }
-                };
-                player.write_packet(&PositionLook {
-                    x: origin.x,
-                    y: origin.y,
-                    z: origin.z,
-                    yaw,
-                    pitch: 0.0,
-                    flags: Default::default(),
-                });
-                continue 'outer
+            let Some(index) = self.teleport_pads.iter().position(|pad| pad.aabb().intersects(&player_aabb)) else {
+                self.standing_on.remove(&player.client_id);
+                continue
+            };
+            // the player stays on the pad until the client has handled the teleport
+            if self.standing_on.insert(player.client_id, index) == Some(index) {
+                continue
             }
+            let pad = &self.teleport_pads[index];
+
+            let mut origin = pad.teleports_to.as_dvec3();
+            origin.x += 0.5;
+            origin.y += 0.5;
+            origin.z += 0.5;
+
+            let yaw = match pad.rotate_type {
+                TeleportPadRotation::Static { yaw } => {
+                    yaw.rotate(room.rotation)
+                },
+                TeleportPadRotation::TowardsEnd => {
+                    let mut target = self.target.as_dvec3();
+                    target.x += 0.5;
+                    target.z += 0.5;
+                    let diff = target - origin;
+                    f64::atan2(-diff.x, diff.z).to_degrees() as f32
+                }
+            };
+            player.write_packet(&PositionLook {
+                x: origin.x,
+                y: origin.y,
+                z: origin.z,
+                yaw,
+                pitch: 0.0,
+                flags: Default::default(),
+            });
+            player.play_sound(Sound::EndermanPortal, 1.0, 1.0);
         }
     }
 
-    // todo interact
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) || position != room.get_world_block_position(CHEST_POSITION) {
+            return;
+        }
+        let world = player.world_mut();
+        world.write_global_packet(&Chat::new(
+            &format!("§aPUZZLE SOLVED! §7{} §emade it through the §dTeleport Maze§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+        ));
+        player.write_packet(&BlockAction {
+            block_pos: position,
+            event_id: 1,
+            event_data: 1,
+            block_id: 54,
+        });
+        room.status = RoomStatus::Complete;
+        world.map.draw_checkmark(room);
+    }
 }
 
 fn pop_with_rng(vec: &mut Vec<(IVec3, IVec3)>, rng: &mut SeededRng) -> (IVec3, IVec3) {
@@ -221,4 +232,51 @@ fn pop_with_rng(vec: &mut Vec<(IVec3, IVec3)>, rng: &mut SeededRng) -> (IVec3, I
     let it = vec[pad_index];
     vec.remove(pad_index);
     it
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn section_of(position: IVec3) -> Option<usize> {
+        SECTION_OFFSETS.iter().position(|offset| {
+            (offset.x..=offset.x + 6).contains(&position.x) && (offset.z..=offset.z + 6).contains(&position.z)
+        })
+    }
+
+    #[test]
+    fn every_seed_is_solvable() {
+        for seed in 0..500 {
+            SeededRng::set_seed(seed);
+            let maze = TeleportMazePuzzle::default();
+
+            // every pad teleports somewhere, and no two pads share a spot
+            let pads = maze.teleport_pads.iter().map(|pad| pad.position).collect::<HashSet<IVec3>>();
+            assert_eq!(pads.len(), SECTION_OFFSETS.len() * 4 + 2, "seed {seed}");
+
+            // walk through every section reachable from the entrance
+            let mut visited: HashSet<usize> = HashSet::new();
+            let entrance = maze.teleport_pads.iter().find(|pad| pad.position == ENTRANCE_PAD).unwrap();
+            let mut queue = vec![section_of(entrance.teleports_to).unwrap()];
+            let mut reached_exit = false;
+
+            while let Some(section) = queue.pop() {
+                if !visited.insert(section) {
+                    continue;
+                }
+                for pad in maze.teleport_pads.iter().filter(|pad| section_of(pad.position) == Some(section)) {
+                    if pad.teleports_to == EXIT_PAD_TO {
+                        reached_exit = true;
+                    }
+                    if let Some(next) = section_of(pad.teleports_to) {
+                        assert_ne!(next, section, "seed {seed} has a pad leading to its own section");
+                        queue.push(next);
+                    }
+                }
+            }
+            assert!(reached_exit, "seed {seed} isn't solvable");
+            assert_eq!(visited.len(), SECTION_OFFSETS.len(), "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use glam::{ivec3, IVec3};
+use rand::Rng;
+use server::block::block_parameter::BlockColor;
+use server::block::Block;
+use server::constants::Sound;
+use server::network::protocol::play::clientbound::Chat;
+use server::{Player, World};
+
+// top left cell of the board, it goes right and down from here as seen from the room
+const BOARD_ORIGIN: IVec3 = ivec3(17, 72, 8);
+
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    // the player
+    X,
+    // the server
+    O,
+}
+
+impl Cell {
+    fn block(&self) -> Block {
+        match self {
+            Cell::Empty => Block::Quartz,
+            Cell::X => Block::Wool { color: BlockColor::Red },
+            Cell::O => Block::Wool { color: BlockColor::Blue },
+        }
+    }
+}
+
+type Board = [Cell; 9];
+
+fn cell_position(index: usize) -> IVec3 {
+    BOARD_ORIGIN - ivec3((index % 3) as i32, (index / 3) as i32, 0)
+}
+
+fn winner(board: &Board) -> Option<Cell> {
+    LINES.iter()
+        .map(|[a, b, c]| [board[*a], board[*b], board[*c]])
+        .find(|[a, b, c]| *a != Cell::Empty && a == b && b == c)
+        .map(|[cell, _, _]| cell)
+}
+
+fn is_full(board: &Board) -> bool {
+    !board.contains(&Cell::Empty)
+}
+
+// score from the server's side, winning sooner and losing later is better
+fn minimax(board: &mut Board, turn: Cell, depth: i32) -> i32 {
+    match winner(board) {
+        Some(Cell::O) => return 10 - depth,
+        Some(_) => return depth - 10,
+        None if is_full(board) => return 0,
+        None => {}
+    }
+
+    let next = if turn == Cell::O { Cell::X } else { Cell::O };
+    let mut best: Option<i32> = None;
+    for index in 0..9 {
+        if board[index] != Cell::Empty {
+            continue;
+        }
+        board[index] = turn;
+        let score = minimax(board, next, depth + 1);
+        board[index] = Cell::Empty;
+
+        let better = match turn {
+            Cell::O => best.is_none_or(|best| score > best),
+            _ => best.is_none_or(|best| score < best),
+        };
+        if better {
+            best = Some(score);
+        }
+    }
+    best.unwrap_or(0)
+}
+
+/// picks the server's move, the first of the best ones so it always plays the same way
+fn best_move(board: &Board) -> Option<usize> {
+    let mut board = *board;
+    let mut best = None;
+    for index in 0..9 {
+        if board[index] != Cell::Empty {
+            continue;
+        }
+        board[index] = Cell::O;
+        let score = minimax(&mut board, Cell::X, 1);
+        board[index] = Cell::Empty;
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((index, score));
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+pub struct TicTacToePuzzle {
+    board: Board,
+}
+
+impl Default for TicTacToePuzzle {
+    fn default() -> Self {
+        // the server always goes first
+        let mut board = [Cell::Empty; 9];
+        board[seeded_rng().random_range(0..9)] = Cell::O;
+        Self {
+            board,
+        }
+    }
+}
+
+impl RoomImplementation for TicTacToePuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for (index, cell) in self.board.iter().enumerate() {
+            let position = room.get_world_block_position(cell_position(index));
+            world.chunk_grid.set_block_at(cell.block(), position.x, position.y, position.z);
+        }
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        let Some(index) = (0..9).find(|index| room.get_world_block_position(cell_position(*index)) == position) else {
+            return;
+        };
+        if self.board[index] != Cell::Empty {
+            return;
+        }
+
+        self.board[index] = Cell::X;
+        let world = player.world_mut();
+        world.chunk_grid.set_block_at(Cell::X.block(), position.x, position.y, position.z);
+        player.play_sound_at(Sound::NoteHat, 1.0, 1.0, position.as_dvec3());
+
+        if winner(&self.board).is_none() && let Some(index) = best_move(&self.board) {
+            self.board[index] = Cell::O;
+            let position = room.get_world_block_position(cell_position(index));
+            world.chunk_grid.set_block_at(Cell::O.block(), position.x, position.y, position.z);
+            player.play_sound_at(Sound::NoteHat, 1.0, 0.5, position.as_dvec3());
+        }
+
+        let world = player.world_mut();
+        match winner(&self.board) {
+            Some(Cell::O) => {
+                world.write_global_packet(&Chat::new(
+                    &format!("§cPUZZLE FAIL! §7{} §elost §bTic Tac Toe§e! §4Y§ci§6k§ee§as§2!", player.profile.username),
+                ));
+                room.status = RoomStatus::Failed;
+            }
+            // can't happen against minimax, but it counts anyway
+            Some(_) => {
+                world.write_global_packet(&Chat::new(
+                    &format!("§aPUZZLE SOLVED! §7{} §ebeat §bTic Tac Toe§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+                ));
+                room.status = RoomStatus::Complete;
+            }
+            None if is_full(&self.board) => {
+                world.write_global_packet(&Chat::new(
+                    &format!("§aPUZZLE SOLVED! §7{} §etied §bTic Tac Toe§e! §4G§co§6o§ed §2j§bo§3b§5!", player.profile.username),
+                ));
+                room.status = RoomStatus::Complete;
+            }
+            None => return,
+        }
+        world.map.draw_checkmark(room);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(cells: &str) -> Board {
+        let mut board = [Cell::Empty; 9];
+        for (index, char) in cells.chars().enumerate() {
+            board[index] = match char {
+                'X' => Cell::X,
+                'O' => Cell::O,
+                _ => Cell::Empty,
+            };
+        }
+        board
+    }
+
+    #[test]
+    fn takes_the_win_over_blocking() {
+        assert_eq!(best_move(&board("OO.XX....")), Some(2));
+    }
+
+    #[test]
+    fn blocks_the_player() {
+        assert_eq!(best_move(&board("XX.O.....")), Some(2));
+        assert_eq!(best_move(&board("O.X.X....")), Some(6));
+    }
+
+    // plays every possible game from every opening, the player should never win
+    #[test]
+    fn never_loses() {
+        fn play(board: &mut Board) {
+            if winner(board).is_some() || is_full(board) {
+                assert_ne!(winner(board), Some(Cell::X), "{board:?}");
+                return;
+            }
+            for index in 0..9 {
+                if board[index] != Cell::Empty {
+                    continue;
+                }
+                let mut next = *board;
+                next[index] = Cell::X;
+                if winner(&next).is_none() && let Some(reply) = best_move(&next) {
+                    next[reply] = Cell::O;
+                }
+                play(&mut next);
+            }
+        }
+
+        for opening in 0..9 {
+            let mut board = [Cell::Empty; 9];
+            board[opening] = Cell::O;
+            play(&mut board);
+        }
+    }
+}
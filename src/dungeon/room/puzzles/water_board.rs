@@ -0,0 +1,346 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use glam::{ivec3, IVec3};
+use rand::prelude::SliceRandom;
+use server::block::block_parameter::{BlockColor, LeverOrientation};
+use server::block::Block;
+use server::constants::Sound;
+use server::network::protocol::play::clientbound::Chat;
+use server::world::chunk::fluid::{BlockAccess, FluidFlow};
+use server::{Player, World};
+use std::collections::HashMap;
+
+// the board is drawn top row first, see the file for what each character means
+const BOARD: &str = include_str!("../../../../data/puzzles/water_board.txt");
+
+// top left of the board, rows go down from here.
+// the board is one block deep, with glass in front and behind it
+const BOARD_ORIGIN: IVec3 = ivec3(8, 84, 26);
+
+const LEVER_COUNT: usize = 6;
+const LEVERS: [IVec3; LEVER_COUNT] = [
+    ivec3(9, 69, 20),
+    ivec3(11, 69, 20),
+    ivec3(13, 69, 20),
+    ivec3(17, 69, 20),
+    ivec3(19, 69, 20),
+    ivec3(21, 69, 20),
+];
+const LEVER_BLOCKS: [Block; LEVER_COUNT] = [
+    Block::GoldBlock,
+    Block::DiamondBlock,
+    Block::EmeraldBlock,
+    Block::LapisLazuliBlock,
+    Block::HardenedClay,
+    Block::CoalBlock,
+];
+const WATER_LEVER: IVec3 = ivec3(15, 69, 20);
+
+const GATE_COUNT: usize = 5;
+const GATE_COLORS: [(BlockColor, &str); GATE_COUNT] = [
+    (BlockColor::Purple, "§5Purple"),
+    (BlockColor::Orange, "§6Orange"),
+    (BlockColor::Blue, "§9Blue"),
+    (BlockColor::Lime, "§aGreen"),
+    (BlockColor::Red, "§cRed"),
+];
+// bottom of each gate, which is a column of wool
+const GATES: [IVec3; GATE_COUNT] = [
+    ivec3(9, 69, 4),
+    ivec3(12, 69, 4),
+    ivec3(15, 69, 4),
+    ivec3(18, 69, 4),
+    ivec3(21, 69, 4),
+];
+const GATE_HEIGHT: i32 = 3;
+
+// vanilla water spreads every 5 ticks
+const FLOW_INTERVAL: usize = 5;
+// the board is small, so anything past this is water going nowhere
+const MAX_FLOW_STEPS: usize = 200;
+
+struct Board {
+    walls: Vec<IVec3>,
+    source: IVec3,
+    // blocks each lever toggles, and whether they are there while the lever is off
+    lever_blocks: [Vec<(IVec3, bool)>; LEVER_COUNT],
+    // water reaching one of these opens the gate
+    gate_slots: [IVec3; GATE_COUNT],
+    width: i32,
+    height: i32,
+}
+
+impl Board {
+    fn parse(board: &str) -> Board {
+        let mut walls = Vec::new();
+        let mut source = None;
+        let mut lever_blocks: [Vec<(IVec3, bool)>; LEVER_COUNT] = Default::default();
+        let mut gate_slots = [None; GATE_COUNT];
+        let mut width = 0;
+
+        let rows = board.lines().collect::<Vec<&str>>();
+        for (row, line) in rows.iter().enumerate() {
+            width = width.max(line.len() as i32);
+            for (column, char) in line.chars().enumerate() {
+                let position = BOARD_ORIGIN + ivec3(column as i32, -(row as i32), 0);
+                match char {
+                    '#' => walls.push(position),
+                    'S' => source = Some(position),
+                    'a'..='f' => lever_blocks[char as usize - 'a' as usize].push((position, true)),
+                    'A'..='F' => lever_blocks[char as usize - 'A' as usize].push((position, false)),
+                    '1'..='5' => gate_slots[char as usize - '1' as usize] = Some(position),
+                    _ => {}
+                }
+            }
+        }
+
+        Board {
+            walls,
+            source: source.expect("water board should have a source"),
+            lever_blocks,
+            gate_slots: gate_slots.map(|slot| slot.expect("water board should have a slot for every gate")),
+            width,
+            height: rows.len() as i32,
+        }
+    }
+
+    fn lever_block(&self, lever: usize, present: bool) -> Block {
+        if present { LEVER_BLOCKS[lever] } else { Block::Air }
+    }
+
+    // every block of the board for these levers, including the glass around it
+    fn blocks(&self, levers: &[bool; LEVER_COUNT]) -> Vec<(IVec3, Block)> {
+        let mut blocks = Vec::new();
+        for column in 0..self.width {
+            for row in 0..self.height {
+                let position = BOARD_ORIGIN + ivec3(column, -row, 0);
+                blocks.push((position - IVec3::Z, Block::Glass));
+                blocks.push((position + IVec3::Z, Block::Glass));
+                blocks.push((position, Block::Air));
+            }
+        }
+        for wall in self.walls.iter() {
+            blocks.push((*wall, Block::StoneBricks));
+        }
+        for (lever, positions) in self.lever_blocks.iter().enumerate() {
+            for (position, when_off) in positions.iter() {
+                blocks.push((*position, self.lever_block(lever, *when_off != levers[lever])));
+            }
+        }
+        blocks
+    }
+
+    // runs the water until it settles, and returns which gates it reached
+    fn reached_gates(&self, levers: &[bool; LEVER_COUNT]) -> [bool; GATE_COUNT] {
+        let mut blocks: HashMap<IVec3, Block> = HashMap::new();
+        for (position, block) in self.blocks(levers) {
+            blocks.set_block(block, position);
+        }
+        let mut flow = FluidFlow::new(vec![self.source]);
+        for _ in 0..MAX_FLOW_STEPS {
+            if !flow.tick(&mut blocks) {
+                break;
+            }
+        }
+        self.gate_slots.map(|slot| flow.contains(slot))
+    }
+}
+
+pub struct WaterBoardPuzzle {
+    board: Board,
+    levers: [bool; LEVER_COUNT],
+    targets: [bool; GATE_COUNT],
+    open_gates: [bool; GATE_COUNT],
+    flow: Option<FluidFlow>,
+    ticks: usize,
+}
+
+impl Default for WaterBoardPuzzle {
+    fn default() -> Self {
+        let board = Board::parse(BOARD);
+        let targets = pick_targets(&board);
+        Self {
+            board,
+            levers: [false; LEVER_COUNT],
+            targets,
+            open_gates: [false; GATE_COUNT],
+            flow: None,
+            ticks: 0,
+        }
+    }
+}
+
+// targets are whatever a random set of levers leads to,
+// so there is always a solution, and it's never the board as it starts
+fn pick_targets(board: &Board) -> [bool; GATE_COUNT] {
+    let starting = board.reached_gates(&[false; LEVER_COUNT]);
+
+    let mut combinations = (0..1 << LEVER_COUNT)
+        .map(|bits: usize| std::array::from_fn(|lever| bits & (1 << lever) != 0))
+        .collect::<Vec<[bool; LEVER_COUNT]>>();
+    combinations.shuffle(&mut seeded_rng());
+
+    combinations.iter()
+        .map(|levers| board.reached_gates(levers))
+        .find(|reached| reached.contains(&true) && *reached != starting)
+        .expect("water board should have a lever combination that reaches different gates")
+}
+
+impl RoomImplementation for WaterBoardPuzzle {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for (position, block) in self.board.blocks(&self.levers) {
+            world.chunk_grid.set_block(block, room.get_world_block_position(position));
+        }
+        for lever in LEVERS.iter().chain([&WATER_LEVER]) {
+            world.chunk_grid.set_block(lever_block(false), room.get_world_block_position(*lever));
+        }
+        for (gate, (color, _)) in GATE_COLORS.iter().enumerate() {
+            set_gate(room, world, gate, false);
+            // show which gates need to be opened above the board
+            if self.targets[gate] {
+                let slot = self.board.gate_slots[gate];
+                let marker = ivec3(slot.x, BOARD_ORIGIN.y + 1, slot.z);
+                world.chunk_grid.set_block(Block::Wool { color: *color }, room.get_world_block_position(marker));
+            }
+        }
+
+        let targets = GATE_COLORS.iter()
+            .zip(self.targets)
+            .filter(|(_, target)| *target)
+            .map(|((_, name), _)| *name)
+            .collect::<Vec<&str>>()
+            .join("§e, ");
+        for player in room.players() {
+            player.send_message(&format!("§eFlip the levers so the water opens the {targets} §egates!"));
+        }
+    }
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        self.ticks += 1;
+        let Some(flow) = &mut self.flow else {
+            return;
+        };
+        if !self.ticks.is_multiple_of(FLOW_INTERVAL) {
+            return;
+        }
+        let flowing = world.chunk_grid.tick_fluid(flow);
+
+        for gate in 0..GATE_COUNT {
+            let slot = room.get_world_block_position(self.board.gate_slots[gate]);
+            if self.open_gates[gate] || !flow.contains(slot) {
+                continue;
+            }
+            self.open_gates[gate] = true;
+            set_gate(room, world, gate, true);
+        }
+
+        // wait for the water to settle, it might still reach a gate it shouldn't
+        if !flowing && self.open_gates == self.targets {
+            world.write_global_packet(&Chat::new("§aPUZZLE SOLVED! §eThe water reached every gate! §4G§co§6o§ed §2j§bo§3b§5!"));
+            room.status = RoomStatus::Complete;
+            world.map.draw_checkmark(room);
+        }
+    }
+
+    fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
+        if !matches!(room.status, RoomStatus::Discovered) {
+            return;
+        }
+        let world = player.world_mut();
+
+        if position == room.get_world_block_position(WATER_LEVER) {
+            let running = self.flow.is_none();
+            world.chunk_grid.set_block(lever_block(running), position);
+            self.restart_water(room, world, running);
+        } else if let Some(lever) = LEVERS.iter().position(|lever| room.get_world_block_position(*lever) == position) {
+            self.levers[lever] = !self.levers[lever];
+            world.chunk_grid.set_block(lever_block(self.levers[lever]), position);
+
+            for (block, when_off) in self.board.lever_blocks[lever].iter() {
+                let block_position = room.get_world_block_position(*block);
+                world.chunk_grid.set_block(self.board.lever_block(lever, *when_off != self.levers[lever]), block_position);
+            }
+            // the board changed under the water, so let it flow again from the top
+            let running = self.flow.is_some();
+            self.restart_water(room, world, running);
+        } else {
+            return;
+        }
+        player.play_sound_at(Sound::RandomWoodClick, 1.0, 0.6, position.as_dvec3());
+    }
+}
+
+impl WaterBoardPuzzle {
+    fn restart_water(&mut self, room: &Room, world: &mut World<Dungeon>, running: bool) {
+        if let Some(mut flow) = self.flow.take() {
+            flow.clear(&mut world.chunk_grid);
+        }
+        for gate in 0..GATE_COUNT {
+            if self.open_gates[gate] {
+                self.open_gates[gate] = false;
+                set_gate(room, world, gate, false);
+            }
+        }
+        if running {
+            self.flow = Some(FluidFlow::new(vec![room.get_world_block_position(self.board.source)]));
+        }
+    }
+}
+
+fn lever_block(powered: bool) -> Block {
+    Block::Lever {
+        orientation: LeverOrientation::UpX,
+        powered,
+    }
+}
+
+fn set_gate(room: &Room, world: &mut World<Dungeon>, gate: usize, open: bool) {
+    let block = if open { Block::Air } else { Block::Wool { color: GATE_COLORS[gate].0 } };
+    for y in 0..GATE_HEIGHT {
+        let position = room.get_world_block_position(GATES[gate] + ivec3(0, y, 0));
+        world.chunk_grid.set_block(block, position);
+    }
+    if open {
+        world.play_sound_at(Sound::RandomWoodClick, 2.0, 0.5, room.get_world_block_position(GATES[gate]).as_dvec3());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::seeded_rng::SeededRng;
+
+    #[test]
+    fn board_has_every_lever_and_gate() {
+        let board = Board::parse(BOARD);
+        assert!(board.lever_blocks.iter().all(|blocks| !blocks.is_empty()));
+        assert_eq!(board.width, 15);
+    }
+
+    #[test]
+    fn targets_are_reachable() {
+        let board = Board::parse(BOARD);
+        let starting = board.reached_gates(&[false; LEVER_COUNT]);
+        for seed in 0..20 {
+            SeededRng::set_seed(seed);
+            let targets = pick_targets(&board);
+            assert_ne!(targets, starting, "seed {seed}");
+            assert!(targets.contains(&true), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn same_seed_same_targets() {
+        let board = Board::parse(BOARD);
+        SeededRng::set_seed(11);
+        let first = pick_targets(&board);
+        SeededRng::set_seed(11);
+        assert_eq!(first, pick_targets(&board));
+    }
+}
@@ -1,15 +1,33 @@
 use crate::dungeon::door::door::Door;
+use crate::dungeon::door::key::{spawn_key, KeyType};
 use crate::dungeon::dungeon::{Dungeon, DUNGEON_ORIGIN};
 use crate::dungeon::dungeon_player::DungeonPlayer;
+use crate::dungeon::room::blood_room::BloodRoom;
+use crate::dungeon::room::breakable::{Breakable, BreakableKind};
+use crate::dungeon::room::puzzles::bomb_defuse::bomb::BombDefusePuzzle;
+use crate::dungeon::room::puzzles::boulder::BoulderPuzzle;
+use crate::dungeon::room::puzzles::creeper_beams::CreeperBeamsPuzzle;
+use crate::dungeon::room::puzzles::higher_or_lower::HigherOrLowerPuzzle;
 use crate::dungeon::room::puzzles::ice_fill::IceFillPuzzle;
+use crate::dungeon::room::puzzles::ice_path::IcePathPuzzle;
 use crate::dungeon::room::puzzles::quiz::QuizPuzzle;
+use crate::dungeon::room::puzzles::teleport_maze::TeleportMazePuzzle;
 use crate::dungeon::room::puzzles::three_weirdos::ThreeWeirdosPuzzle;
-use crate::dungeon::room::room_data::RoomData;
-use crate::dungeon::room::room_implementation::{MobRoom, RoomImplementation};
-use glam::{dvec3, ivec3, usize, IVec3};
+use crate::dungeon::room::puzzles::tic_tac_toe::TicTacToePuzzle;
+use crate::dungeon::room::puzzles::water_board::WaterBoardPuzzle;
+use crate::dungeon::room::room_data::{RoomData, RoomType, SecretKind};
+use crate::dungeon::room::mob_room::MobRoom;
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::room::secrets::Secret;
+use crate::dungeon::room::trap_room::TrapRoom;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, usize, DVec3, IVec3};
 use server::block::rotatable::Rotate;
 use server::block::Block;
-use server::network::protocol::play::clientbound::Chat;
+use server::constants::Sound;
+use server::entity::entity::MinecraftEntity;
+use server::network::protocol::play::clientbound::{BlockAction, Chat, CollectItem};
+use server::network::binary::var_int::VarInt;
 use server::types::aabb::AABB;
 use server::types::chat_component::ChatComponent;
 use server::types::direction::Direction;
@@ -51,8 +69,15 @@ pub struct Room {
     pub data: RoomData,
 
     pub status: RoomStatus,
+    // ticks into the run when this room was cleared
+    pub cleared_at: Option<usize>,
 
     pub players: HashMap<ClientId, Rc<UnsafeCell<Player<DungeonPlayer>>>>,
+    // spawned when the room is discovered
+    pub keys: Vec<KeyType>,
+    pub secrets: Vec<Secret>,
+    // crypts and weak walls
+    pub breakables: Vec<Breakable>,
     pub implementation: UnsafeCell<Box<dyn RoomImplementation>>
 }
 
@@ -101,21 +126,41 @@ impl Room {
 
         let implementation: UnsafeCell<Box<dyn RoomImplementation>> = match room_data.name.as_str() {
             "Three Weirdos" => UnsafeCell::new(Box::new(ThreeWeirdosPuzzle::default())),
-            "Quiz" => UnsafeCell::new(Box::new(QuizPuzzle {})),
-            // "Teleport Maze" => UnsafeCell::new(Box::new(TeleportMazePuzzle::default())),
+            "Quiz" => UnsafeCell::new(Box::new(QuizPuzzle::default())),
+            "Teleport Maze" => UnsafeCell::new(Box::new(TeleportMazePuzzle::default())),
             "Ice Fill" => UnsafeCell::new(Box::new(IceFillPuzzle::default())),
-            _ => UnsafeCell::new(Box::new(MobRoom {})),
+            "Ice Path" => UnsafeCell::new(Box::new(IcePathPuzzle::default())),
+            "Creeper Beams" => UnsafeCell::new(Box::new(CreeperBeamsPuzzle::default())),
+            "Water Board" => UnsafeCell::new(Box::new(WaterBoardPuzzle::default())),
+            "Tic Tac Toe" => UnsafeCell::new(Box::new(TicTacToePuzzle::default())),
+            "Boulder" => UnsafeCell::new(Box::new(BoulderPuzzle::default())),
+            "Higher Or Lower" => UnsafeCell::new(Box::new(HigherOrLowerPuzzle::default())),
+            "Bomb Defuse" => UnsafeCell::new(Box::new(BombDefusePuzzle::default())),
+            _ if room_data.room_type == RoomType::Blood => UnsafeCell::new(Box::new(BloodRoom::default())),
+            _ if room_data.room_type == RoomType::Trap => UnsafeCell::new(Box::new(TrapRoom::new(&room_data, segments.len()))),
+            _ => UnsafeCell::new(Box::new(MobRoom::new(&room_data, segments.len()))),
         };
 
-        Self {
+        let mut room = Self {
             segments,
             room_bounds,
             rotation,
             data: room_data,
             status: RoomStatus::Undiscovered,
+            cleared_at: None,
             implementation,
             players: HashMap::new(),
-        }
+            keys: Vec::new(),
+            secrets: Vec::new(),
+            breakables: Vec::new(),
+        };
+        room.secrets = room.data.secrets.iter()
+            .map(|secret| Secret::new(secret, room.get_world_block_position(secret.position)))
+            .collect();
+        let crypts = room.data.crypts.iter().map(|region| Breakable::new(BreakableKind::Crypt, region, &room));
+        let weak_walls = room.data.weak_walls.iter().map(|region| Breakable::new(BreakableKind::WeakWall, region, &room));
+        room.breakables = crypts.chain(weak_walls).collect();
+        room
     }
 
     pub fn neighbours(&self) -> impl Iterator<Item = &RoomNeighbour> {
@@ -197,15 +242,115 @@ impl Room {
         self.status = RoomStatus::Discovered;
         let implementation = unsafe { &mut *self.implementation.get() };
         implementation.discover(self, world);
+        self.spawn_keys(world);
+        for secret in self.secrets.iter_mut() {
+            secret.spawn(world);
+        }
 
         world.map.draw_room(self)
     }
 
+    pub fn spawn_keys(&mut self, world: &mut World<Dungeon>) {
+        for (index, key) in std::mem::take(&mut self.keys).into_iter().enumerate() {
+            spawn_key(world, self, key, index);
+        }
+    }
+
+    pub fn secrets_found(&self) -> usize {
+        self.secrets.iter().filter(|secret| secret.found).count()
+    }
+
+    fn find_secret(&mut self, index: usize, world: &mut World<Dungeon>) {
+        if !self.secrets[index].find() {
+            return;
+        }
+        world.secrets_found += 1;
+        world.play_sound_at(Sound::RandomOrb, 1.0, 1.0, self.secrets[index].position.as_dvec3() + 0.5);
+
+        let (found, total) = (self.secrets_found(), self.secrets.len());
+        for player in self.players() {
+            player.send_action_bar(&format!("§7{found}/{total} Secrets"));
+        }
+        // turns the checkmark green
+        if found == total {
+            world.map.draw_checkmark(self);
+        }
+    }
+
+    fn click_secret(&mut self, world: &mut World<Dungeon>, position: IVec3) {
+        let Some(index) = self.secrets.iter().position(|secret| secret.position == position && !secret.found) else {
+            return;
+        };
+        let IVec3 { x, y, z } = position;
+        match self.secrets[index].kind {
+            SecretKind::Chest => {
+                world.write_local_packet(position.as_dvec3(), &BlockAction {
+                    block_pos: position,
+                    event_id: 1,
+                    event_data: 1,
+                    block_id: 54,
+                });
+                world.play_sound_at(Sound::RandomChestOpen, 0.5, 1.0, position.as_dvec3() + 0.5);
+            }
+            SecretKind::Essence => world.chunk_grid.set_block_at(Block::Air, x, y, z),
+            SecretKind::Lever => {
+                if let Block::Lever { orientation, powered } = world.chunk_grid.get_block_at(x, y, z) {
+                    world.chunk_grid.set_block_at(Block::Lever { orientation, powered: !powered }, x, y, z);
+                }
+            }
+            SecretKind::Item | SecretKind::Bat => return,
+        }
+        self.find_secret(index, world);
+    }
+
+    fn pick_up_items(&mut self, world: &mut World<Dungeon>) {
+        for index in 0..self.secrets.len() {
+            let secret = &self.secrets[index];
+            let (SecretKind::Item, false, Some(entity)) = (secret.kind, secret.found, secret.entity) else {
+                continue;
+            };
+            let aabb = AABB::from_width_height(1.0, 1.0).offset(secret.position.as_dvec3() + dvec3(0.5, 0.0, 0.5));
+            let Some(player) = self.players().find(|player| !player.is_dead && player.collision_aabb().intersects(&aabb)) else {
+                continue;
+            };
+            // a full inventory can't pick it up, same as vanilla
+            if !DungeonPlayer::give_item(player, secret.item) {
+                continue;
+            }
+            if let Some(item) = world.entities.get_entity(entity).get::<MinecraftEntity<Dungeon>>() {
+                world.write_local_packet(item.position, &CollectItem {
+                    item_entity_id: VarInt(item.id),
+                    entity_id: VarInt(player.entity_id),
+                });
+            }
+            world.remove_entity(entity);
+            self.find_secret(index, world);
+        }
+    }
+
+    /// blows up every crypt and weak wall the explosion reaches
+    pub fn explode(&mut self, world: &mut World<Dungeon>, center: DVec3, radius: f64) {
+        for breakable in self.breakables.iter_mut() {
+            if !breakable.broken && breakable.in_range(center, radius) {
+                breakable.blow_up(world);
+            }
+        }
+    }
+
     pub fn tick(&mut self, world: &mut World<Dungeon>) {
+        self.pick_up_items(world);
         let implementation = unsafe { &mut *self.implementation.get() };
         implementation.tick(self, world)
     }
 
+    pub fn on_entity_death(&mut self, world: &mut World<Dungeon>, entity: Entity) {
+        if let Some(index) = self.secrets.iter().position(|secret| secret.entity == Some(entity)) {
+            self.find_secret(index, world);
+        }
+        let implementation = unsafe { &mut *self.implementation.get() };
+        implementation.on_entity_death(self, world, entity)
+    }
+
     pub fn interact_with_block(
         room_rc: &Rc<RefCell<Room>>,
         player: &mut Player<DungeonPlayer>,
@@ -216,6 +361,7 @@ impl Room {
         }
 
         let mut room = room_rc.borrow_mut();
+        room.click_secret(player.world_mut(), position);
         let implementation = unsafe { &mut *room.implementation.get() };
         implementation.interact(&mut room, player, position);
 
@@ -243,6 +389,12 @@ impl Room {
         })
     }
 
+    pub fn attack_entity(room_rc: &Rc<RefCell<Room>>, player: &mut Player<DungeonPlayer>, entity: Entity) {
+        let mut room = room_rc.borrow_mut();
+        let implementation = unsafe { &mut *room.implementation.get() };
+        implementation.attack(&mut room, player, entity);
+    }
+
     pub fn on_start_dig(room_rc: &Rc<RefCell<Room>>, player: &mut Player<DungeonPlayer>, position: IVec3) {
         if Self::try_open_door(room_rc, player, position) {
             return;
@@ -262,11 +414,8 @@ impl Room {
                 continue;
             }
             if !door.can_open(world) {
-                // todo: proper chat message and sound
-                // player.write_packet(&Chat {
-                //     component: ChatComponent::new("no key"),
-                //     chat_type: 0,
-                // });
+                player.send_message("§cYou do not have the key for this door!");
+                player.play_sound(Sound::VillagerNo, 1.0, 1.0);
                 continue;
             }
             door.open(world);
@@ -355,4 +504,18 @@ fn get_rotation_from_segments(segments: &[RoomSegment]) -> Direction {
         },
         _ => unreachable!(),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl Room {
+    /// a 1x2 going south, which gets rotated to face east
+    pub fn test_room(data: RoomData) -> Room {
+        let segment = |z| RoomSegment {
+            x: 0,
+            z,
+            neighbours: [const { None }; 4],
+            player_ref_count: 0,
+        };
+        Room::new(vec![segment(0), segment(1)], data)
+    }
+}
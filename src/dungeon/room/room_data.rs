@@ -1,48 +1,46 @@
+use crate::dungeon::entities::dungeon_mob::MobKind;
 use crate::dungeon::room::room::{Room, RoomSegment};
 use crate::dungeon::seeded_rng::seeded_rng;
+use glam::{ivec3, IVec3};
+use anyhow::bail;
 use rand::seq::IteratorRandom;
-use serde_json::Value;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
 use server::block::Block;
 use server::utils::hasher::deterministic_hasher::DeterministicHashMap;
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::rc::Rc;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub enum RoomShape {
+    #[serde(rename = "1x1")]
     OneByOne,         // Fairy room, doors can vary
+    #[serde(rename = "1x1_E")]
     OneByOneEnd,      // A dead end, only one door
+    #[serde(rename = "1x1_X")]
     OneByOneCross,    // Four doors
+    #[serde(rename = "1x1_I")]
     OneByOneStraight, // Two doors opposite each other
+    #[serde(rename = "1x1_L")]
     OneByOneBend,     // Two doors making an L bend
+    #[serde(rename = "1x1_3")]
     OneByOneTriple,   // Two opposite with one in the middle
 
+    #[serde(rename = "1x2")]
     OneByTwo,
+    #[serde(rename = "1x3")]
     OneByThree,
+    #[serde(rename = "1x4")]
     OneByFour,
+    #[serde(rename = "2x2")]
     TwoByTwo,
     L,
+    #[serde(skip)]
     Empty, // Shouldn't happen probably
 }
 
 impl RoomShape {
-    pub fn from_str(value: &str) -> RoomShape {
-        match value {
-            "1x1" => Self::OneByOne,
-            "1x1_E" => Self::OneByOneEnd,
-            "1x1_X" => Self::OneByOneCross,
-            "1x1_I" => Self::OneByOneStraight,
-            "1x1_L" => Self::OneByOneBend,
-            "1x1_3" => Self::OneByOneTriple,
-            "1x2" => Self::OneByTwo,
-            "1x3" => Self::OneByThree,
-            "1x4" => Self::OneByFour,
-            "2x2" => Self::TwoByTwo,
-            "L" => Self::L,
-            _ => unimplemented!(),
-        }
-    }
-
     pub fn from_segments(segments: &[RoomSegment]) -> RoomShape {
         let unique_x = segments
             .iter()
@@ -104,7 +102,8 @@ impl RoomShape {
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, Deserialize)]
+#[serde(from = "String")]
 pub enum RoomType {
     Normal,
     Puzzle,
@@ -116,9 +115,10 @@ pub enum RoomType {
     Rare,
 }
 
-impl RoomType {
-    pub fn from_str(value: &str) -> RoomType {
-        match value {
+// unknown types have always been loaded as normal rooms
+impl From<String> for RoomType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
             "normal" => RoomType::Normal,
             "puzzle" => RoomType::Puzzle,
             "yellow" => RoomType::Yellow,
@@ -127,69 +127,300 @@ impl RoomType {
             "entrance" => RoomType::Entrance,
             "trap" => RoomType::Trap,
             "rare" => RoomType::Rare,
-            _ => RoomType::Normal,
+            _ => {
+                eprintln!("warning: unknown room type {value:?}, using normal");
+                RoomType::Normal
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawMobSpawn")]
+pub struct MobSpawn {
+    pub kind: MobKind,
+    // relative to the room, like puzzle positions
+    pub position: IVec3,
+    pub starred: bool,
+}
+
+// positions are stored as flat x, y, z fields
+#[derive(Deserialize)]
+struct RawMobSpawn {
+    x: i32,
+    y: i32,
+    z: i32,
+    #[serde(rename = "type")]
+    kind: MobKind,
+    #[serde(default)]
+    starred: bool,
+}
+
+impl From<RawMobSpawn> for MobSpawn {
+    fn from(raw: RawMobSpawn) -> Self {
+        MobSpawn {
+            kind: raw.kind,
+            position: ivec3(raw.x, raw.y, raw.z),
+            starred: raw.starred,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    Chest,
+    // wither essence skull
+    Essence,
+    Item,
+    Bat,
+    Lever,
+}
+
+/// what an item secret drops
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretItem {
+    #[default]
+    Superboom,
+    EnderPearl,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawSecret")]
+pub struct SecretData {
+    pub kind: SecretKind,
+    // relative to the room, the chest/skull/lever block or where the item or bat spawns
+    pub position: IVec3,
+    // only used by item secrets
+    pub item: SecretItem,
+}
+
+#[derive(Deserialize)]
+struct RawSecret {
+    x: i32,
+    y: i32,
+    z: i32,
+    #[serde(rename = "type")]
+    kind: SecretKind,
+    #[serde(default)]
+    item: SecretItem,
+}
+
+impl From<RawSecret> for SecretData {
+    fn from(raw: RawSecret) -> Self {
+        SecretData {
+            kind: raw.kind,
+            position: ivec3(raw.x, raw.y, raw.z),
+            item: raw.item,
+        }
+    }
+}
+
+/// a box of blocks that superboom can blow up, used for crypts and weak walls
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(from = "RawRegion")]
+pub struct RegionData {
+    // lowest corner, relative to the room
+    pub position: IVec3,
+    // size along x, y and z, before rotation
+    pub width: i32,
+    pub height: i32,
+    pub length: i32,
+}
+
+#[derive(Deserialize)]
+struct RawRegion {
+    x: i32,
+    y: i32,
+    z: i32,
+    width: i32,
+    height: i32,
+    length: i32,
+}
+
+impl From<RawRegion> for RegionData {
+    fn from(raw: RawRegion) -> Self {
+        RegionData {
+            position: ivec3(raw.x, raw.y, raw.z),
+            width: raw.width,
+            height: raw.height,
+            length: raw.length,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrusherDirection {
+    North,
+    East,
+    South,
+    West,
+    Up,
+    Down,
+}
+
+impl CrusherDirection {
+    pub fn offset(&self) -> IVec3 {
+        match self {
+            CrusherDirection::North => ivec3(0, 0, -1),
+            CrusherDirection::East => ivec3(1, 0, 0),
+            CrusherDirection::South => ivec3(0, 0, 1),
+            CrusherDirection::West => ivec3(-1, 0, 0),
+            CrusherDirection::Up => ivec3(0, 1, 0),
+            CrusherDirection::Down => ivec3(0, -1, 0),
+        }
+    }
+}
+
+/// a wall of blocks that slides out in a direction and back again.
+// not checked against the crushers in DungeonData yet, validate reports any that don't match
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawCrusher")]
+pub struct CrusherData {
+    // corner of the face that moves, relative to the room
+    pub position: IVec3,
+    pub direction: CrusherDirection,
+    // size of the face, across the direction it moves in
+    pub width: u32,
+    pub height: u32,
+    // how many blocks it moves before going back
+    pub length: u32,
+    pub ticks_per_block: u32,
+    // ticks spent fully extended or retracted before moving again
+    pub pause_ticks: u32,
+}
+
+#[derive(Deserialize)]
+struct RawCrusher {
+    x: i32,
+    y: i32,
+    z: i32,
+    direction: CrusherDirection,
+    width: u32,
+    height: u32,
+    length: u32,
+    #[serde(default = "default_ticks_per_block")]
+    ticks_per_block: u32,
+    #[serde(default = "default_pause_ticks")]
+    pause_ticks: u32,
+}
+
+fn default_ticks_per_block() -> u32 {
+    5
+}
+
+fn default_pause_ticks() -> u32 {
+    20
+}
+
+impl TryFrom<RawCrusher> for CrusherData {
+    type Error = &'static str;
+
+    fn try_from(raw: RawCrusher) -> Result<Self, Self::Error> {
+        if raw.ticks_per_block == 0 {
+            return Err("ticks_per_block should be above 0");
+        }
+        if raw.length == 0 {
+            return Err("length should be above 0");
+        }
+        Ok(CrusherData {
+            position: ivec3(raw.x, raw.y, raw.z),
+            direction: raw.direction,
+            width: raw.width,
+            height: raw.height,
+            length: raw.length,
+            ticks_per_block: raw.ticks_per_block,
+            pause_ticks: raw.pause_ticks,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct RoomData {
     pub name: String,
     pub id: String,
     pub shape: RoomShape,
+    #[serde(rename = "type")]
     pub room_type: RoomType,
     pub bottom: i32,
     pub width: i32,
     pub length: i32,
     pub height: i32,
     // do we need to keep this once loaded into world?
+    #[serde(deserialize_with = "deserialize_block_data")]
     pub block_data: Vec<Block>,
-    pub crusher_data: Vec<Value>, // Needs to be parsed when rooms are generated
+    // a crusher that can't be parsed only skips itself, the error is kept for validate
+    #[serde(default, rename = "crushers", deserialize_with = "deserialize_crushers")]
+    pub crusher_data: Vec<Result<CrusherData, String>>,
+    #[serde(default)]
+    pub mobs: Vec<MobSpawn>,
+    #[serde(default)]
+    pub secrets: Vec<SecretData>,
+    #[serde(default)]
+    pub crypts: Vec<RegionData>,
+    #[serde(default)]
+    pub weak_walls: Vec<RegionData>,
 }
 
-impl RoomData {
-    pub fn from_raw_json(raw_data: &str) -> RoomData {
-        let json_data: Value = serde_json::from_str(raw_data).unwrap(); // surely we just parse into a struct instead of doing this indexing?
-
-        let name = json_data["name"].as_str().unwrap().to_string();
-        let id = json_data["id"].as_str().unwrap().to_string();
-        let shape = RoomShape::from_str(json_data["shape"].as_str().unwrap());
-        let room_type = RoomType::from_str(json_data["type"].as_str().unwrap());
-        let bottom = json_data["bottom"].as_number().unwrap().as_u64().unwrap() as i32;
-        let width = json_data["width"].as_number().unwrap().as_u64().unwrap() as i32;
-        let length = json_data["length"].as_number().unwrap().as_u64().unwrap() as i32;
-        let height = json_data["height"].as_number().unwrap().as_u64().unwrap() as i32;
-
-        let crusher_data: Vec<Value> = json_data["crushers"]
-            .as_array()
-            .unwrap_or(&Vec::new())
-            .to_vec();
-
-        let hex_data = json_data["block_data"].as_str().unwrap();
-
-        let mut block_data: Vec<Block> = Vec::new();
+fn deserialize_crushers<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Result<CrusherData, String>>, D::Error> {
+    let crushers = Vec::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(crushers
+        .into_iter()
+        .enumerate()
+        .map(|(index, crusher)| {
+            serde_path_to_error::deserialize(&crusher)
+                .map_err(|error| format!("crusher {index} {crusher}: {}", describe_error(&error)))
+        })
+        .collect())
+}
 
-        for i in (0..hex_data.len()).step_by(4) {
-            let hex_str = hex_data.get(i..i + 4).unwrap();
+// names the field that couldn't be parsed, if it was in one
+fn describe_error(error: &serde_path_to_error::Error<serde_json::Error>) -> String {
+    if error.path().iter().next().is_none() {
+        error.inner().to_string()
+    } else {
+        format!("field `{}`: {}", error.path(), error.inner())
+    }
+}
 
-            let num = u16::from_str_radix(hex_str, 16).unwrap();
-            let block = Block::from(num);
+// every block is 4 hex characters
+fn deserialize_block_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Block>, D::Error> {
+    let hex_data = String::deserialize(deserializer)?;
+    if !hex_data.is_ascii() || hex_data.len() % 4 != 0 {
+        return Err(D::Error::custom("length should be a multiple of 4 hex characters"));
+    }
+    (0..hex_data.len())
+        .step_by(4)
+        .map(|index| {
+            let hex_str = &hex_data[index..index + 4];
+            match u16::from_str_radix(hex_str, 16) {
+                Ok(num) => Ok(Block::from(num)),
+                Err(_) => Err(D::Error::custom(format!("invalid hex {hex_str:?} at character {index}"))),
+            }
+        })
+        .collect()
+}
 
-            block_data.push(block)
+impl RoomData {
+    /// parses a room data file, errors name the field that couldn't be parsed
+    pub fn from_raw_json(raw_data: &str) -> anyhow::Result<RoomData> {
+        let deserializer = &mut serde_json::Deserializer::from_str(raw_data);
+        match serde_path_to_error::deserialize(deserializer) {
+            Ok(room_data) => Ok(room_data),
+            Err(error) => bail!("{}", describe_error(&error)),
         }
+    }
 
-        RoomData {
-            name,
-            id,
-            shape,
-            room_type,
-            bottom,
-            width,
-            length,
-            height,
-            block_data,
-            crusher_data,
+    // position is relative to the room, before rotation
+    pub fn get_block(&self, position: IVec3) -> Block {
+        let IVec3 { x, y, z } = position;
+        if x < 0 || z < 0 || y < self.bottom || x >= self.width || z >= self.length {
+            return Block::Air
         }
+        let index = x + z * self.width + (y - self.bottom) * self.width * self.length;
+        self.block_data.get(index as usize).copied().unwrap_or(Block::Air)
     }
 
     pub fn dummy() -> RoomData {
@@ -204,6 +435,10 @@ impl RoomData {
             height: 30,
             block_data: vec![],
             crusher_data: vec![],
+            mobs: vec![],
+            secrets: vec![],
+            crypts: vec![],
+            weak_walls: vec![],
         }
     }
 }
@@ -225,3 +460,72 @@ pub fn get_random_data_with_type(
         .unwrap_or(&RoomData::dummy())
         .clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM: &str = r#"{
+        "name": "Test", "id": "1", "shape": "1x1_E", "type": "normal",
+        "bottom": 68, "width": 1, "length": 1, "height": 2, "block_data": "00010000",
+        "crushers": [{ "x": 1, "y": 70, "z": 2, "direction": "up", "width": 3, "height": 1, "length": 4 }],
+        "mobs": [{ "type": "zombie_soldier", "x": 5, "y": 69, "z": 6, "starred": true }],
+        "secrets": [
+            { "type": "essence", "x": 3, "y": 71, "z": 4 },
+            { "type": "item", "x": 5, "y": 70, "z": 6, "item": "ender_pearl" }
+        ],
+        "crypts": [{ "x": 7, "y": 68, "z": 8, "width": 1, "height": 2, "length": 3 }]
+    }"#;
+
+    #[test]
+    fn parses_room_data() {
+        let room_data = RoomData::from_raw_json(ROOM).unwrap();
+        assert_eq!(room_data.shape, RoomShape::OneByOneEnd);
+        assert_eq!(room_data.block_data.len(), 2);
+        assert_eq!(room_data.crusher_data, vec![Ok(CrusherData {
+            position: ivec3(1, 70, 2),
+            direction: CrusherDirection::Up,
+            width: 3,
+            height: 1,
+            length: 4,
+            ticks_per_block: 5,
+            pause_ticks: 20,
+        })]);
+        assert_eq!(room_data.mobs, vec![MobSpawn { kind: MobKind::ZombieSoldier, position: ivec3(5, 69, 6), starred: true }]);
+        assert_eq!(room_data.secrets, vec![
+            SecretData { kind: SecretKind::Essence, position: ivec3(3, 71, 4), item: SecretItem::Superboom },
+            SecretData { kind: SecretKind::Item, position: ivec3(5, 70, 6), item: SecretItem::EnderPearl },
+        ]);
+        assert_eq!(room_data.crypts, vec![RegionData { position: ivec3(7, 68, 8), width: 1, height: 2, length: 3 }]);
+        assert!(room_data.weak_walls.is_empty());
+    }
+
+    #[test]
+    fn unknown_room_types_are_normal() {
+        let room_data = RoomData::from_raw_json(&ROOM.replace(r#""type": "normal""#, r#""type": "mystery""#)).unwrap();
+        assert_eq!(room_data.room_type, RoomType::Normal);
+        let room_data = RoomData::from_raw_json(&ROOM.replace(r#""type": "normal""#, r#""type": "trap""#)).unwrap();
+        assert_eq!(room_data.room_type, RoomType::Trap);
+    }
+
+    #[test]
+    fn bad_crushers_are_kept_as_errors() {
+        let crusher = |from: &str, to: &str| RoomData::from_raw_json(&ROOM.replace(from, to)).unwrap().crusher_data.remove(0);
+
+        assert!(crusher(r#""direction": "up""#, r#""direction": "sideways""#).unwrap_err().contains("field `direction`: unknown variant `sideways`"));
+        assert!(crusher(r#""length": 4"#, r#""length": 0"#).unwrap_err().contains("length should be above 0"));
+        assert!(crusher(r#""length": 4"#, r#""length": 4, "ticks_per_block": 0"#).is_err());
+        assert_eq!(crusher(r#""length": 4"#, r#""length": 4, "ticks_per_block": 2"#).unwrap().ticks_per_block, 2);
+    }
+
+    #[test]
+    fn errors_name_the_field() {
+        let error = |from: &str, to: &str| RoomData::from_raw_json(&ROOM.replace(from, to)).unwrap_err().to_string();
+
+        assert!(error(r#""width": 1,"#, "").contains("missing field `width`"));
+        assert!(error("1x1_E", "3x3").starts_with("field `shape`: unknown variant `3x3`"));
+        assert!(error("zombie_soldier", "zombie").starts_with("field `mobs[0].type`: unknown variant `zombie`"));
+        assert!(error("essence", "skull").starts_with("field `secrets[0].type`: unknown variant `skull`"));
+        assert!(error("00010000", "0001zz00").starts_with("field `block_data`: invalid hex \"zz00\""));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::dungeon::dungeon::Dungeon;
 use crate::dungeon::dungeon_player::DungeonPlayer;
-use crate::dungeon::room::room::{Room, RoomStatus};
+use crate::dungeon::room::room::Room;
+use bevy_ecs::entity::Entity;
 use glam::IVec3;
 use server::{Player, World};
 
@@ -16,12 +17,14 @@ pub trait RoomImplementation {
     fn interact(&mut self, room: &mut Room, player: &mut Player<DungeonPlayer>, position: IVec3) {
 
     }
-}
 
-pub struct MobRoom;
+    // called for the room the player is in, when they hit any entity
+    fn attack(&mut self, _room: &mut Room, _player: &mut Player<DungeonPlayer>, _entity: Entity) {
 
-impl RoomImplementation for MobRoom {
-    fn discover(&mut self, room: &mut Room, _: &mut World<Dungeon>) {
-        room.status = RoomStatus::Complete
     }
-}
\ No newline at end of file
+
+    // called for every discovered room, when any entity with health dies
+    fn on_entity_death(&mut self, _room: &mut Room, _world: &mut World<Dungeon>, _entity: Entity) {
+
+    }
+}
@@ -0,0 +1,105 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::health::Health;
+use crate::dungeon::entities::item_appearance::ItemAppearance;
+use crate::dungeon::items::dungeon_items::{DungeonItem, DungeonItems};
+use crate::dungeon::items::ender_pearl::EnderPearl;
+use crate::dungeon::items::superboom::SuperboomTNT;
+use crate::dungeon::room::room_data::{SecretData, SecretItem, SecretKind};
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, IVec3};
+use server::constants::EntityVariant;
+use server::entity::components::MobAppearance;
+use server::entity::entity_metadata::{BatMetadata, EntityMetadata};
+use server::inventory::item_stack::ItemStack;
+use server::World;
+
+const BAT_HEALTH: f32 = 100.0;
+
+impl From<SecretItem> for DungeonItems {
+    fn from(item: SecretItem) -> Self {
+        match item {
+            SecretItem::Superboom => SuperboomTNT.into(),
+            SecretItem::EnderPearl => EnderPearl.into(),
+        }
+    }
+}
+
+pub struct Secret {
+    pub kind: SecretKind,
+    // in the world, already rotated with the room
+    pub position: IVec3,
+    pub item: DungeonItems,
+    pub found: bool,
+    // items and bats, spawned when the room is discovered
+    pub entity: Option<Entity>,
+}
+
+impl Secret {
+    pub fn new(data: &SecretData, position: IVec3) -> Self {
+        Self {
+            kind: data.kind,
+            position,
+            item: data.item.into(),
+            found: false,
+            entity: None,
+        }
+    }
+
+    /// returns true the first time, every secret only counts once per run
+    pub fn find(&mut self) -> bool {
+        !std::mem::replace(&mut self.found, true)
+    }
+
+    pub fn spawn(&mut self, world: &mut World<Dungeon>) {
+        let position = self.position.as_dvec3() + dvec3(0.5, 0.0, 0.5);
+        self.entity = match self.kind {
+            SecretKind::Item => Some(world.spawn_entity(
+                position,
+                0.0,
+                0.0,
+                ItemAppearance {
+                    item: ItemStack {
+                        stack_size: 1,
+                        ..self.item.item_stack()
+                    },
+                },
+                (),
+            )),
+            SecretKind::Bat => Some(world.spawn_entity(
+                position,
+                0.0,
+                0.0,
+                MobAppearance {
+                    variant: EntityVariant::Bat,
+                    metadata: EntityMetadata::Bat(BatMetadata::default()),
+                },
+                Health::new(BAT_HEALTH),
+            )),
+            SecretKind::Chest | SecretKind::Essence | SecretKind::Lever => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dungeon::room::room::Room;
+    use crate::dungeon::room::room_data::{RoomData, SecretData, SecretItem, SecretKind};
+    use glam::ivec3;
+    use server::types::direction::Direction;
+
+    #[test]
+    fn secrets_rotate_and_count_once() {
+        let data = RoomData {
+            secrets: vec![SecretData { kind: SecretKind::Chest, position: ivec3(3, 70, 5), item: SecretItem::Superboom }],
+            ..RoomData::dummy()
+        };
+        let mut room = Room::test_room(data);
+        assert_eq!(room.rotation, Direction::East);
+        assert_eq!(room.secrets[0].position, room.get_world_block_position(ivec3(3, 70, 5)));
+        assert_ne!(room.secrets[0].position, room.get_corner_pos() + ivec3(3, 2, 5));
+
+        assert!(room.secrets[0].find());
+        assert!(!room.secrets[0].find());
+        assert_eq!(room.secrets_found(), 1);
+    }
+}
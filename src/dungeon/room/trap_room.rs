@@ -0,0 +1,163 @@
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::arrow::{ArrowAppearance, ArrowBehaviour};
+use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::room::crusher::{cycle_ticks, Crusher};
+use crate::dungeon::room::mob_room::MobRoom;
+use crate::dungeon::room::room::Room;
+use crate::dungeon::room::room_data::RoomData;
+use crate::dungeon::room::room_implementation::RoomImplementation;
+use crate::dungeon::seeded_rng::seeded_rng;
+use bevy_ecs::entity::Entity;
+use glam::{dvec3, ivec3, IVec3};
+use rand::Rng;
+use server::block::Block;
+use server::constants::Sound;
+use server::entity::entity::MinecraftEntity;
+use server::types::direction::Direction3D;
+use server::World;
+
+const ARROW_INTERVAL: u32 = 40;
+const ARROW_SPEED: f64 = 1.2;
+const ARROW_LIFETIME: u32 = 100;
+
+struct Dispenser {
+    // relative to the room until it's discovered, then in the world
+    position: IVec3,
+    facing: IVec3,
+    // so they don't all fire at once
+    delay: u32,
+}
+
+/// crushers, arrow dispensers and the mobs that need killing to get through
+pub struct TrapRoom {
+    mobs: MobRoom,
+    crushers: Vec<Crusher>,
+    dispensers: Vec<Dispenser>,
+    // ticks anyone has spent in the room since it was discovered
+    ticks: u32,
+}
+
+impl TrapRoom {
+    pub fn new(room_data: &RoomData, segment_count: usize) -> Self {
+        // timings are picked on creation so they're always the same for a given seed
+        let mut rng = seeded_rng();
+        let crushers = room_data.crusher_data.iter().flatten()
+            .map(|data| Crusher::new(data.clone(), rng.random_range(0..cycle_ticks(data))))
+            .collect();
+
+        let mut dispensers = Vec::new();
+        for (index, block) in room_data.block_data.iter().enumerate() {
+            if !matches!(block, Block::Dispenser { .. }) {
+                continue;
+            }
+            let index = index as i32;
+            dispensers.push(Dispenser {
+                position: ivec3(
+                    index % room_data.width,
+                    room_data.bottom + index / (room_data.width * room_data.length),
+                    (index / room_data.width) % room_data.length,
+                ),
+                facing: IVec3::ZERO,
+                delay: rng.random_range(0..ARROW_INTERVAL),
+            });
+        }
+
+        Self {
+            mobs: MobRoom::new(room_data, segment_count),
+            crushers,
+            dispensers,
+            ticks: 0,
+        }
+    }
+
+    fn place_dispensers(&mut self, room: &Room, world: &World<Dungeon>) {
+        for dispenser in self.dispensers.iter_mut() {
+            dispenser.position = room.get_world_block_position(dispenser.position);
+            let IVec3 { x, y, z } = dispenser.position;
+            // the block in the world is already rotated
+            if let Block::Dispenser { direction, .. } = world.chunk_grid.get_block_at(x, y, z) {
+                dispenser.facing = match direction {
+                    Direction3D::Down => ivec3(0, -1, 0),
+                    Direction3D::Up => ivec3(0, 1, 0),
+                    Direction3D::North => ivec3(0, 0, -1),
+                    Direction3D::South => ivec3(0, 0, 1),
+                    Direction3D::West => ivec3(-1, 0, 0),
+                    Direction3D::East => ivec3(1, 0, 0),
+                };
+            }
+        }
+    }
+
+    fn fire_arrows(&self, world: &mut World<Dungeon>) {
+        for dispenser in self.dispensers.iter() {
+            if dispenser.facing == IVec3::ZERO || !(self.ticks + dispenser.delay).is_multiple_of(ARROW_INTERVAL) {
+                continue;
+            }
+            let facing = dispenser.facing.as_dvec3();
+            // from just outside the front of the dispenser
+            let position = dispenser.position.as_dvec3() + 0.5 + facing * 0.8 - dvec3(0.0, 0.25, 0.0);
+            let arrow = world.spawn_entity(
+                position,
+                0.0,
+                0.0,
+                ArrowAppearance,
+                (
+                    ArrowBehaviour,
+                    Lifetime {
+                        ticks: ARROW_LIFETIME,
+                    },
+                ),
+            );
+            if let Some(mut entity) = world.entities.get_entity_mut(arrow).get_mut::<MinecraftEntity<Dungeon>>() {
+                entity.velocity = facing * ARROW_SPEED;
+            }
+            world.play_sound_at(Sound::RandomBow, 0.5, 1.0, position);
+        }
+    }
+}
+
+impl RoomImplementation for TrapRoom {
+    fn discover(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        for crusher in self.crushers.iter_mut() {
+            crusher.place(room);
+        }
+        self.place_dispensers(room, world);
+        self.mobs.discover(room, world);
+    }
+
+    fn tick(&mut self, room: &mut Room, world: &mut World<Dungeon>) {
+        // everything waits until someone is around, a crusher left mid move
+        // finishes the block it was on and carries on when they come back
+        if room.is_undiscovered() || room.players().next().is_none() {
+            return;
+        }
+        for crusher in self.crushers.iter() {
+            crusher.tick(room, world, self.ticks);
+        }
+        self.fire_arrows(world);
+        self.ticks += 1;
+    }
+
+    fn on_entity_death(&mut self, room: &mut Room, world: &mut World<Dungeon>, entity: Entity) {
+        self.mobs.on_entity_death(room, world, entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dungeon::room::room_data::RoomType;
+
+    const ROOM: &str = r#"{
+        "name": "Trap", "id": "1", "shape": "1x1", "type": "trap",
+        "bottom": 68, "width": 1, "length": 1, "height": 1, "block_data": "0000",
+        "crushers": [{ "x": 4, "y": 69, "z": 10, "direction": "east", "width": 5, "height": 4, "length": 6 }]
+    }"#;
+
+    #[test]
+    fn builds_crushers_from_room_data() {
+        let room_data = RoomData::from_raw_json(ROOM).unwrap();
+        assert_eq!(room_data.room_type, RoomType::Trap);
+        assert_eq!(TrapRoom::new(&room_data, 1).crushers.len(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::dungeon::floor::Floor;
+
+/// everything the score is calculated from
+pub struct ScoreStats {
+    pub floor: Floor,
+    pub seconds: u32,
+    // 0 to 100
+    pub cleared_percent: i32,
+    pub deaths: usize,
+    pub failed_puzzles: usize,
+    pub secrets_found: usize,
+    pub secrets_total: usize,
+    pub crypts: usize,
+    pub mimic_killed: bool,
+    pub prince_killed: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub skill: i32,
+    pub exploration: i32,
+    pub speed: i32,
+    pub bonus: i32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScoreRank {
+    SPlus,
+    S,
+    A,
+    B,
+    C,
+    D,
+}
+
+impl ScoreRank {
+    pub fn display(&self) -> &'static str {
+        match self {
+            ScoreRank::SPlus => "§6S+",
+            ScoreRank::S => "§eS",
+            ScoreRank::A => "§5A",
+            ScoreRank::B => "§aB",
+            ScoreRank::C => "§9C",
+            ScoreRank::D => "§cD",
+        }
+    }
+}
+
+impl Score {
+    pub fn total(&self) -> i32 {
+        self.skill + self.exploration + self.speed + self.bonus
+    }
+
+    pub fn rank(&self) -> ScoreRank {
+        match self.total() {
+            300.. => ScoreRank::SPlus,
+            270.. => ScoreRank::S,
+            230.. => ScoreRank::A,
+            160.. => ScoreRank::B,
+            100.. => ScoreRank::C,
+            _ => ScoreRank::D,
+        }
+    }
+}
+
+pub fn calculate_score(stats: &ScoreStats) -> Score {
+    let cleared = stats.cleared_percent.clamp(0, 100) as f32 / 100.0;
+
+    let skill = 20 + (80.0 * cleared).floor() as i32
+        - 10 * stats.failed_puzzles as i32
+        - 2 * stats.deaths as i32;
+
+    // floors with no secrets to find shouldn't be punished for it
+    let secrets_needed = (stats.secrets_total * stats.floor.secrets_required_percent()).div_ceil(100);
+    let secrets = if secrets_needed > 0 {
+        (stats.secrets_found as f32 / secrets_needed as f32).min(1.0)
+    } else {
+        1.0
+    };
+    let exploration = (60.0 * cleared).floor() as i32 + (40.0 * secrets).floor() as i32;
+
+    let bonus = stats.crypts.min(5) as i32
+        + if stats.mimic_killed { 2 } else { 0 }
+        + if stats.prince_killed { 1 } else { 0 };
+
+    Score {
+        skill: skill.clamp(20, 100),
+        exploration,
+        speed: speed_score(stats.seconds, stats.floor.time_limit_seconds()),
+        bonus,
+    }
+}
+
+// loses points faster the further over the time limit it gets
+fn speed_score(seconds: u32, time_limit: u32) -> i32 {
+    let over = (seconds as f32 - time_limit as f32) / time_limit as f32 * 100.0;
+    let score = match over {
+        ..=0.0 => 100.0,
+        ..=20.0 => 100.0 - over / 2.0,
+        ..=40.0 => 90.0 - (over - 20.0) / 4.0,
+        ..=50.0 => 85.0 - (over - 40.0) / 5.0,
+        ..=60.0 => 83.0 - (over - 50.0) / 6.0,
+        _ => 81.0 - (over - 60.0) / 7.0,
+    };
+    (score.floor() as i32).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perfect_run() -> ScoreStats {
+        ScoreStats {
+            floor: Floor::F7,
+            seconds: 300,
+            cleared_percent: 100,
+            deaths: 0,
+            failed_puzzles: 0,
+            secrets_found: 50,
+            secrets_total: 50,
+            crypts: 5,
+            mimic_killed: false,
+            prince_killed: false,
+        }
+    }
+
+    #[test]
+    fn perfect_run_is_s_plus() {
+        let score = calculate_score(&perfect_run());
+        assert_eq!(score, Score { skill: 100, exploration: 100, speed: 100, bonus: 5 });
+        assert_eq!(score.total(), 305);
+        assert_eq!(score.rank(), ScoreRank::SPlus);
+    }
+
+    #[test]
+    fn deaths_and_failed_puzzles_lower_skill() {
+        let stats = ScoreStats {
+            deaths: 2,
+            failed_puzzles: 1,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).skill, 86);
+
+        let stats = ScoreStats {
+            deaths: 50,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).skill, 20);
+    }
+
+    #[test]
+    fn exploration_uses_clear_and_secrets() {
+        let stats = ScoreStats {
+            cleared_percent: 50,
+            secrets_found: 25,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).exploration, 30 + 20);
+
+        // lower floors need less secrets
+        let stats = ScoreStats {
+            floor: Floor::F1,
+            secrets_found: 15,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).exploration, 100);
+
+        let stats = ScoreStats {
+            secrets_total: 0,
+            secrets_found: 0,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).exploration, 100);
+    }
+
+    #[test]
+    fn speed_drops_after_time_limit() {
+        assert_eq!(speed_score(840, 840), 100);
+        assert_eq!(speed_score(1008, 840), 90);
+        assert_eq!(speed_score(1176, 840), 85);
+        assert!(speed_score(100_000, 840) >= 0);
+    }
+
+    #[test]
+    fn bonus_caps_crypts() {
+        let stats = ScoreStats {
+            crypts: 12,
+            mimic_killed: true,
+            prince_killed: true,
+            ..perfect_run()
+        };
+        assert_eq!(calculate_score(&stats).bonus, 8);
+    }
+
+    #[test]
+    fn rank_thresholds() {
+        let rank = |total| Score { skill: total, exploration: 0, speed: 0, bonus: 0 }.rank();
+        assert_eq!(rank(300), ScoreRank::SPlus);
+        assert_eq!(rank(299), ScoreRank::S);
+        assert_eq!(rank(270), ScoreRank::S);
+        assert_eq!(rank(269), ScoreRank::A);
+        assert_eq!(rank(99), ScoreRank::D);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::dungeon::door::door::DoorType;
+use crate::dungeon::dungeon::{Dungeon, DUNGEON_ORIGIN};
+use crate::dungeon::layout_generator::generate_layout;
+use crate::dungeon::room::puzzles::ice_fill::load_ice_fill_layers;
+use crate::dungeon::room::room_data::{RoomData, RoomShape, RoomType};
+use crate::dungeon::seeded_rng::SeededRng;
+use crate::load_room_data;
+use anyhow::{bail, Context};
+use server::utils::hasher::deterministic_hasher::DeterministicHashMap;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+const DEFAULT_LAYOUTS_PATH: &str = "DungeonData/dungeon_layouts.txt";
+const GRID_SIZE: usize = 6;
+const CELL_WIDTH: usize = 5;
+
+const USAGE: &str = "\
+usage:
+  RustClear validate [layouts file]
+      loads all room data and every layout in the file, and reports any that fail.
+      defaults to DungeonData/dungeon_layouts.txt
+
+  RustClear inspect [--seed <seed>] [--layouts <path> --line <line>] [--layout <layout>]
+      prints a layout and the rooms chosen for it.
+      layouts are generated from the seed unless a layout or layouts file is given";
+
+/// runs a command instead of the server, returns false if it isn't a command
+pub fn run_command(args: &[String]) -> anyhow::Result<bool> {
+    match args.first().map(String::as_str) {
+        Some("validate") => validate(&args[1..])?,
+        Some("inspect") => inspect(&args[1..])?,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn validate(args: &[String]) -> anyhow::Result<()> {
+    let path = args.first().map(String::as_str).unwrap_or(DEFAULT_LAYOUTS_PATH);
+    let layouts = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read layouts file {path}"))?;
+
+    let (room_data_storage, room_data_errors) = load_room_data();
+    for (name, error) in room_data_errors.iter() {
+        println!("room data {name}: {error}");
+    }
+    println!("loaded {} room data files, {} failed", room_data_storage.len(), room_data_errors.len());
+
+    let mut crusher_errors = 0;
+    for room_data in room_data_storage.values() {
+        for error in room_data.crusher_data.iter().filter_map(|crusher| crusher.as_ref().err()) {
+            println!("room data {}: {error}", room_data.name);
+            crusher_errors += 1;
+        }
+    }
+
+    let (_, ice_fill_errors) = load_ice_fill_layers();
+    for error in ice_fill_errors.iter() {
+        println!("ice fill {error}");
+    }
+
+    let mut layout_count = 0;
+    let mut failed = 0;
+    for (index, layout) in layouts.lines().enumerate() {
+        if layout.trim().is_empty() {
+            continue;
+        }
+        layout_count += 1;
+        if let Err(error) = parse_layout(layout.trim(), &room_data_storage) {
+            println!("line {}: {error}", index + 1);
+            failed += 1;
+        }
+    }
+    println!("checked {layout_count} layouts, {failed} failed");
+
+    if failed != 0 || crusher_errors != 0 || !room_data_errors.is_empty() || !ice_fill_errors.is_empty() {
+        bail!("validation failed")
+    }
+    Ok(())
+}
+
+fn inspect(args: &[String]) -> anyhow::Result<()> {
+    let mut seed = None;
+    let mut layouts_path = None;
+    let mut line = None;
+    let mut layout = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            bail!("missing value for {arg}\n\n{USAGE}")
+        };
+        match arg.as_str() {
+            "--seed" => seed = Some(value.parse::<u64>().with_context(|| format!("invalid seed {value}"))?),
+            "--layouts" => layouts_path = Some(value.clone()),
+            "--line" => line = Some(value.parse::<usize>().with_context(|| format!("invalid line {value}"))?),
+            "--layout" => layout = Some(value.clone()),
+            _ => bail!("unknown option {arg}\n\n{USAGE}"),
+        }
+    }
+
+    // room data is picked with the seeded rng too
+    let seed = seed.unwrap_or_else(rand::random);
+    SeededRng::set_seed(seed);
+
+    let layout = match (layout, layouts_path) {
+        (Some(layout), _) => layout,
+        (None, Some(path)) => {
+            let layouts = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read layouts file {path}"))?;
+            let line = line.unwrap_or(1);
+            let Some(layout) = layouts.lines().nth(line.saturating_sub(1)) else {
+                bail!("{path} doesn't have a line {line}")
+            };
+            layout.trim().to_string()
+        }
+        (None, None) => generate_layout()?,
+    };
+
+    let (room_data_storage, room_data_errors) = load_room_data();
+    for (name, error) in room_data_errors.iter() {
+        println!("skipping room data {name}: {error}");
+    }
+    let dungeon = parse_layout(&layout, &room_data_storage)?;
+
+    println!("seed {seed}");
+    println!("{layout}");
+    println!();
+    print!("{}", render_layout(&dungeon));
+    Ok(())
+}
+
+fn parse_layout(layout: &str, room_data_storage: &DeterministicHashMap<usize, RoomData>) -> anyhow::Result<Dungeon> {
+    match catch_panic(|| Dungeon::from_string(layout, room_data_storage)) {
+        Ok(result) => result,
+        Err(error) => bail!("panicked while parsing: {error}"),
+    }
+}
+
+// parsing layouts still unwraps in places instead of returning errors
+fn catch_panic<T>(callback: impl FnOnce() -> T) -> Result<T, String> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = catch_unwind(AssertUnwindSafe(callback));
+    std::panic::set_hook(hook);
+    result.map_err(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    "unknown panic".to_string()
+}
+
+/// draws the dungeon grid, followed by what was picked for each room.
+///
+/// rooms are labeled by their index, with a letter for special rooms,
+/// doors are drawn as D for normal doors, W for wither, B for blood and E for entrance.
+pub fn render_layout(dungeon: &Dungeon) -> String {
+    let mut grid: [Option<usize>; GRID_SIZE * GRID_SIZE] = [None; GRID_SIZE * GRID_SIZE];
+    for (index, room) in dungeon.rooms.iter().enumerate() {
+        for segment in room.borrow().segments.iter() {
+            grid[segment.x + segment.z * GRID_SIZE] = Some(index);
+        }
+    }
+    let room_at = |x: usize, z: usize| grid[x + z * GRID_SIZE];
+
+    // door markers, to the east and south of each cell
+    let mut east_doors = [None; GRID_SIZE * GRID_SIZE];
+    let mut south_doors = [None; GRID_SIZE * GRID_SIZE];
+    for door in dungeon.doors.iter() {
+        let door = door.borrow();
+        let x = (door.x - DUNGEON_ORIGIN.x) as usize;
+        let z = (door.z - DUNGEON_ORIGIN.y) as usize;
+        let marker = match door.get_type() {
+            DoorType::Normal => 'D',
+            DoorType::Wither => 'W',
+            DoorType::Blood => 'B',
+            DoorType::Entrance => 'E',
+        };
+        if x % 32 == 31 {
+            east_doors[x / 32 + z / 32 * GRID_SIZE] = Some(marker);
+        } else {
+            south_doors[x / 32 + z / 32 * GRID_SIZE] = Some(marker);
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str(&horizontal_line(|_| None));
+
+    for z in 0..GRID_SIZE {
+        out.push('|');
+        for x in 0..GRID_SIZE {
+            let label = match room_at(x, z) {
+                Some(index) => room_label(dungeon, index),
+                None => String::new(),
+            };
+            out.push_str(&format!("{label:^CELL_WIDTH$}"));
+
+            let separator = match east_doors[x + z * GRID_SIZE] {
+                Some(marker) => marker,
+                None if x + 1 < GRID_SIZE && room_at(x, z).is_some() && room_at(x, z) == room_at(x + 1, z) => ' ',
+                None => '|',
+            };
+            out.push(separator);
+        }
+        out.push('\n');
+
+        if z + 1 < GRID_SIZE {
+            out.push_str(&horizontal_line(|x| match south_doors[x + z * GRID_SIZE] {
+                Some(marker) => Some(marker),
+                None if room_at(x, z).is_some() && room_at(x, z) == room_at(x, z + 1) => Some(' '),
+                None => None,
+            }));
+        } else {
+            out.push_str(&horizontal_line(|_| None));
+        }
+    }
+
+    out.push('\n');
+    for (index, room) in dungeon.rooms.iter().enumerate() {
+        let room = room.borrow();
+        out.push_str(&format!(
+            "{:>4}  {:<9} {:<17} {:<6} {}\n",
+            room_label(dungeon, index),
+            format!("{:?}", room.data.room_type),
+            format!("{:?}", RoomShape::from_segments(&room.segments)),
+            format!("{:?}", room.rotation),
+            room.data.name,
+        ));
+    }
+    out
+}
+
+// marker is what goes in the middle of the line under each cell, none draws a wall
+fn horizontal_line(marker: impl Fn(usize) -> Option<char>) -> String {
+    let mut line = String::from("+");
+    for x in 0..GRID_SIZE {
+        match marker(x) {
+            Some(' ') => line.push_str(&" ".repeat(CELL_WIDTH)),
+            Some(marker) => line.push_str(&format!("{marker:-^CELL_WIDTH$}")),
+            None => line.push_str(&"-".repeat(CELL_WIDTH)),
+        }
+        line.push('+');
+    }
+    line.push('\n');
+    line
+}
+
+fn room_label(dungeon: &Dungeon, index: usize) -> String {
+    let prefix = match dungeon.rooms[index].borrow().data.room_type {
+        RoomType::Entrance => "E",
+        RoomType::Blood => "B",
+        RoomType::Fairy => "F",
+        RoomType::Puzzle => "P",
+        RoomType::Trap => "T",
+        RoomType::Yellow => "Y",
+        RoomType::Normal | RoomType::Rare => "",
+    };
+    format!("{prefix}{index}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_draws_every_room_and_door() {
+        SeededRng::set_seed(3);
+        let dungeon = Dungeon::from_string(&generate_layout().unwrap(), &DeterministicHashMap::default()).unwrap();
+        let render = render_layout(&dungeon);
+
+        let lines = render.lines().collect::<Vec<&str>>();
+        // grid, blank line, then a line for each room
+        assert_eq!(lines.len(), GRID_SIZE * 2 + 1 + 1 + dungeon.rooms.len());
+        assert!(lines[..GRID_SIZE * 2 + 1].iter().all(|line| line.len() == GRID_SIZE * (CELL_WIDTH + 1) + 1));
+
+        let door_markers = lines[..GRID_SIZE * 2 + 1].iter()
+            .flat_map(|line| line.chars())
+            .filter(|char| matches!(char, 'D' | 'W' | 'B' | 'E'))
+            .count();
+        let labels = dungeon.rooms.iter()
+            .map(|room| room.borrow())
+            .filter(|room| matches!(room.data.room_type, RoomType::Entrance | RoomType::Blood))
+            .count();
+        assert_eq!(door_markers, dungeon.doors.len() + labels);
+    }
+
+    #[test]
+    fn invalid_layouts_are_reported() {
+        let room_data_storage = DeterministicHashMap::default();
+        assert!(parse_layout("0101", &room_data_storage).is_err());
+        // entrance with a door leading to nothing
+        let layout = format!("01{}0{}", "00".repeat(35), "9".repeat(59));
+        assert!(parse_layout(&layout, &room_data_storage).is_err());
+    }
+}
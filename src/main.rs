@@ -1,20 +1,27 @@
 #![allow(clippy::collapsible_if, clippy::too_many_arguments, clippy::new_without_default)]
 
+use crate::config::{config, Config};
 use crate::dungeon::door::door::DoorType;
+use crate::dungeon::door::key::KeyBehaviour;
 use crate::dungeon::door::sound_emitter::DoorSoundEmitter;
-use crate::dungeon::dungeon::{Dungeon, DungeonState};
+use crate::dungeon::dungeon::Dungeon;
+use crate::dungeon::entities::arrow::ArrowBehaviour;
 use crate::dungeon::entities::components::Lifetime;
+use crate::dungeon::entities::health::Health;
 use crate::dungeon::entities::moving_block_behaviour::MovingBlockBehaviour;
 use crate::dungeon::entities::npc::NPCBehaviour;
 use crate::dungeon::items::ender_pearl::EnderPearlBehaviour;
 use crate::dungeon::items::spirit_sceptre::SceptreBatBehaviour;
+use crate::dungeon::layout_generator::generate_layout;
 use crate::dungeon::menus::MortMenu;
+use crate::dungeon::room::puzzles::ice_fill::init_ice_fill_layers;
+use crate::dungeon::room::puzzles::ice_path::SlidingBehaviour;
 use crate::dungeon::room::room_data::RoomData;
 use crate::dungeon::seeded_rng::{seeded_rng, SeededRng};
 use anyhow::bail;
 use bevy_ecs::component::Component;
 use glam::ivec3;
-use include_dir::include_dir;
+use include_dir::{include_dir, Dir};
 use rand::prelude::IndexedRandom;
 use server::block::rotatable::Rotate;
 use server::block::Block;
@@ -32,33 +39,46 @@ use std::time::Duration;
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::UnboundedSender as Sender;
 
+mod config;
 mod dungeon;
+mod inspect;
 
 pub fn initialize_world(tx: Sender<NetworkThreadMessage>) -> anyhow::Result<World<Dungeon>> {
-    let rng_seed: u64 = rand::random();
-    SeededRng::set_seed(18158556563918935308);
-
     // tp maze, ice fill, boulder seed 18158556563918935308
     // three weirdo seed 16795237019042391353
+    let rng_seed = config().seed.unwrap_or_else(rand::random);
+    SeededRng::set_seed(rng_seed);
 
     println!("seed {rng_seed}");
 
-    let dungeon_layouts = include_str!("../DungeonData/dungeon_layouts.txt")
-        .split("\n")
-        .collect::<Vec<&str>>();
-
-    let layout = dungeon_layouts.choose(&mut seeded_rng()).unwrap();
-
-    // todo: fix room heights from moody's room data
-    let room_data_storage = &room_data();
-    let door_type_blocks = &door_block_data();
-
-    let dungeon = Dungeon::from_string(layout, room_data_storage)?;
+    let dungeon = generate_dungeon()?;
     // if you do anything with entities or anything that has a pointer to world.
     // once world moves out of this functions scope
     // it will move in the stack causing those pointers to be invalid,
     // this can be fixed by using Box<T> if it is required
     let mut world = World::new(tx, dungeon);
+    load_dungeon(&mut world);
+
+    Ok(world)
+}
+
+/// picks or generates a layout using the seeded rng, and creates a dungeon from it
+pub fn generate_dungeon() -> anyhow::Result<Dungeon> {
+    let layout = match config().dungeon_layouts().choose(&mut seeded_rng()) {
+        Some(layout) => layout.clone(),
+        None => generate_layout()?,
+    };
+
+    // todo: fix room heights from moody's room data
+    let room_data_storage = &room_data();
+    let mut dungeon = Dungeon::from_string(&layout, room_data_storage)?;
+    dungeon.floor = config().floor;
+    Ok(dungeon)
+}
+
+/// places the blocks for every room and door in the dungeon
+pub fn load_dungeon(world: &mut World<Dungeon>) {
+    let door_type_blocks = &door_block_data();
 
     for room in world.extension.rooms.iter() {
         if room.borrow().data.name.to_lowercase().contains("ice fill") {
@@ -69,8 +89,6 @@ pub fn initialize_world(tx: Sender<NetworkThreadMessage>) -> anyhow::Result<Worl
     for door in world.extension.doors.iter() {
         door.borrow().load_into_world(&mut world.chunk_grid, door_type_blocks)
     }
-
-    Ok(world)
 }
 
 // test
@@ -114,7 +132,7 @@ pub fn spawn_mort(world: &mut World<Dungeon>) {
             },
             Interactable::<Dungeon> {
                 callback: |_, player| {
-                    if let DungeonState::Started { .. } = player.world().state {
+                    if !player.world().in_lobby() {
                         return;
                     }
                     player.open_container(OpenContainer::Menu(Box::new(MortMenu {})))
@@ -127,12 +145,22 @@ pub fn spawn_mort(world: &mut World<Dungeon>) {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let text = ChatComponent::new("RustClear").color(MCColors::Gold)
-        .append(ChatComponent::new(" version ").color(MCColors::Gray))
-        .append(ChatComponent::new(env!("CARGO_PKG_VERSION")).color(MCColors::Green));
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if inspect::run_command(&args)? {
+        return Ok(());
+    }
+    let config = Config::init(args.into_iter())?;
+    init_ice_fill_layers()?;
+
+    let text = match &config.motd {
+        Some(motd) => ChatComponent::new(motd),
+        None => ChatComponent::new("RustClear").color(MCColors::Gold)
+            .append(ChatComponent::new(" version ").color(MCColors::Gray))
+            .append(ChatComponent::new(env!("CARGO_PKG_VERSION")).color(MCColors::Green)),
+    };
 
-    let status = Status::new(0, 1, text, "");
-    let (tx, mut rx) = start_network("127.0.0.1:4972", status);
+    let status = Status::new(0, config.max_players, text, config.favicon_data());
+    let (tx, mut rx) = start_network(config.address(), status);
 
     let mut world = initialize_world(tx)?;
     spawn_mort(&mut world);
@@ -144,6 +172,10 @@ async fn main() -> anyhow::Result<()> {
     world.entities.register_behaviour::<EnderPearlBehaviour>();
     world.entities.register_behaviour::<SceptreBatBehaviour>();
     world.entities.register_behaviour::<Lifetime>();
+    world.entities.register_behaviour::<Health>();
+    world.entities.register_behaviour::<SlidingBehaviour>();
+    world.entities.register_behaviour::<KeyBehaviour>();
+    world.entities.register_behaviour::<ArrowBehaviour>();
 
     // for x in -200..0 {
     //     for z in -200..0 {
@@ -203,25 +235,50 @@ async fn main() -> anyhow::Result<()> {
     }
 }
 
+// invalid room data files are skipped, so one broken room doesn't stop the server
 fn room_data() -> DeterministicHashMap<usize, RoomData> {
-    let rooms_directory = include_dir!("DungeonData/room_data/");
-    let room_data_storage: DeterministicHashMap<usize, RoomData> = rooms_directory.entries()
-        .iter()
-        .map(|file| {
-            let file = file.as_file().unwrap();
-
-            let contents = file.contents_utf8().unwrap();
-            let name = file.path().file_name().unwrap().to_str().unwrap();
-            let room_data = RoomData::from_raw_json(contents);
-
-            let name_parts: Vec<&str> = name.split(",").collect();
-            let room_id = name_parts.first().unwrap().parse::<usize>().unwrap();
-
-            (room_id, room_data)
-        }).collect();
+    let (room_data_storage, errors) = load_room_data();
+    for (name, error) in errors.iter() {
+        eprintln!("warning: skipping room data {name}: {error}");
+    }
+    for room_data in room_data_storage.values() {
+        for error in room_data.crusher_data.iter().filter_map(|crusher| crusher.as_ref().err()) {
+            eprintln!("warning: skipping {error} in room {}", room_data.name);
+        }
+    }
     room_data_storage
 }
 
+// loads every room data file that can be loaded, and the errors for the ones that couldn't be
+fn load_room_data() -> (DeterministicHashMap<usize, RoomData>, Vec<(String, String)>) {
+    static ROOMS_DIRECTORY: Dir = include_dir!("DungeonData/room_data/");
+    let mut room_data_storage = DeterministicHashMap::default();
+    let mut errors = Vec::new();
+
+    for file in ROOMS_DIRECTORY.files() {
+        let name = file.path().to_string_lossy().to_string();
+        let Some(Ok(room_id)) = name.split(",").next().map(str::parse::<usize>) else {
+            errors.push((name, "file name should start with the room id".to_string()));
+            continue;
+        };
+        if room_data_storage.contains_key(&room_id) {
+            errors.push((name, format!("room id {room_id} is used more than once")));
+            continue;
+        }
+        let Some(contents) = file.contents_utf8() else {
+            errors.push((name, "file isn't valid utf-8".to_string()));
+            continue;
+        };
+        match RoomData::from_raw_json(contents) {
+            Ok(room_data) => {
+                room_data_storage.insert(room_id, room_data);
+            }
+            Err(error) => errors.push((name, error.to_string())),
+        }
+    }
+    (room_data_storage, errors)
+}
+
 fn door_block_data() -> DeterministicHashMap<DoorType, Vec<Vec<Block>>> {
     let door_data: Vec<Vec<Block>> = include_str!("../DungeonData/door_data/doors.txt")
         .split("\n")